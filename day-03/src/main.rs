@@ -1,29 +1,81 @@
-#![feature(bool_to_option)]
+use std::{env, error::Error, fs::read_to_string};
 
-use std::{error::Error, fs::File, io::BufReader, iter, io::prelude::*};
+type Slope = (usize, usize);
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let reader = BufReader::new(File::open("input/input1.txt")?);
+    let input = read_to_string("input/input1.txt")?;
+    let map: Vec<&str> = input.lines().collect();
 
-    let offset_bases: Vec<(usize, usize)> = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
-    let mut matches: Vec<usize> = iter::repeat(0).take(offset_bases.len()).collect();
+    let offset_bases: Vec<Slope> = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+    let matches: Vec<usize> = offset_bases.iter().map(|slope| count_trees(&map, *slope)).collect();
 
-    for (idx, line) in reader.lines().skip(1).enumerate() {
-        let row = line?;
-        let current_row = idx + 1; // since we skip the first row
-
-        for (offset_idx, (row_peek, column_peek)) in offset_bases.iter().enumerate() {
-            if current_row % row_peek != 0 { continue; }
-            let offset = ((current_row / row_peek) * column_peek) % row.len();
-
-            if row.chars().nth(offset).unwrap() == '#' {
-                matches[offset_idx] += 1;
-            }
+    if env::args().any(|arg| arg == "--render") {
+        for slope in offset_bases.iter() {
+            println!("Slope {:?}:\n{}", slope, render_path(&map, *slope));
         }
     }
 
     println!("Slopes: {:#?}", offset_bases);
-    println!("Solution: {:#?} ({})", matches, matches.iter().fold(1, |a, b| a * b));
+    println!("Solution: {:#?} ({})", matches, matches.iter().product::<usize>());
 
     Ok(())
 }
+
+fn path_cells(map: &[&str], (row_peek, column_peek): Slope) -> impl Iterator<Item = (usize, usize)> {
+    (1..map.len())
+        .filter(move |current_row| current_row % row_peek == 0)
+        .map(move |current_row| (current_row, (current_row / row_peek) * column_peek))
+}
+
+fn is_tree(map: &[&str], row: usize, column: usize) -> bool {
+    let row = map[row].as_bytes();
+    row[column % row.len()] == b'#'
+}
+
+fn count_trees(map: &[&str], slope: Slope) -> usize {
+    path_cells(map, slope).filter(|(row, column)| is_tree(map, *row, *column)).count()
+}
+
+fn render_path(map: &[&str], slope: Slope) -> String {
+    let width = map.first().map_or(0, |row| row.len());
+    if width == 0 {
+        return String::new();
+    }
+
+    let furthest_column = path_cells(map, slope).map(|(_, column)| column).max().unwrap_or(0);
+    let repeats = furthest_column / width + 1;
+
+    let mut rendered: Vec<Vec<char>> = map.iter().map(|row| row.repeat(repeats).chars().collect()).collect();
+    for (row, column) in path_cells(map, slope) {
+        rendered[row][column] = if is_tree(map, row, column) { 'X' } else { 'O' };
+    }
+
+    rendered.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<String>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n.#...##..#.\n..#.##.....\n.#.#.#....#\n.#........#\n#.##...#...\n#...##....#\n.#..#...#.#";
+
+    #[test]
+    fn test_count_trees() {
+        let map: Vec<&str> = SAMPLE.lines().collect();
+        let counts: Vec<usize> = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)].iter().map(|slope| count_trees(&map, *slope)).collect();
+        assert_eq!(counts, vec![2, 7, 3, 4, 2]);
+    }
+
+    #[test]
+    fn test_render_path() {
+        let map: Vec<&str> = SAMPLE.lines().collect();
+        let rendered = render_path(&map, (1, 3));
+        let rows: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(rows[0], "..##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[2], ".#....X..#..#....#..#..#....#..#.");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...X.#");
+        assert_eq!(rendered.matches('X').count(), 7);
+    }
+}
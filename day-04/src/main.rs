@@ -1,72 +1,51 @@
-use std::{error::Error, fs::File, io::BufReader, collections::{HashSet, HashMap}, io::prelude::*};
+mod passport;
+mod record;
+mod report;
+mod schema;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let required_fields: HashSet<&str> = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"].iter().cloned().collect();
+use std::{convert::TryFrom, env, error::Error, fs::read_to_string, collections::HashSet};
 
-    let mut reader = BufReader::new(File::open("input/input1.txt")?);
-    let mut input: String = String::new();
-    let _ = reader.read_to_string(&mut input);
+use passport::Passport;
+use record::parse_records;
+use report::{InvalidPassport, Report};
+use schema::Schema;
 
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let schema_path = args.iter().find(|arg| !arg.starts_with("--")).map_or("schema.toml", |path| path.as_str());
+    let schema = Schema::load(schema_path)?;
+    let required_fields: HashSet<&str> = schema.required_fields().collect();
 
-    let num_valid_passports = input.split("\n\n").fold(0, |count, line| {
-        let passport_fields: HashSet<&str> = line.split_whitespace().map(|pair| pair.split(':').next().unwrap()).collect();
+    let input = read_to_string("input/input1.txt")?;
 
-        count + required_fields.is_subset(&passport_fields) as i32
-    });
+    let num_valid_passports = parse_records(&input)
+        .filter_map(|record| record.ok())
+        .filter(|record| required_fields.iter().all(|key| record.fields.contains_key(key)))
+        .count();
 
     println!("Part 1: {}", num_valid_passports);
 
-    let num_valid_passports = input.split("\n\n").fold(0, |count, line| {
-        let passport: HashMap<&str, &str> = line.split_whitespace().map(|pair| {
-            let mut pair_iter = pair.split(':');
-            (pair_iter.next().unwrap(), pair_iter.next().unwrap())
-        }).collect();
-        
-        let passport_fields: HashSet<&str> = passport.keys().cloned().collect();
-        count + (
-            required_fields.is_subset(&passport_fields) && 
-            passport.iter().all(|(k, v)| is_field_valid(*k, *v))
-        ) as i32
-    });
-
-    println!("Part 2: {}", num_valid_passports);
+    let report = Report::new(&schema, parse_records(&input));
+
+    println!("Part 2: {}", report.num_valid());
+
+    let valid_passports = || -> Result<Vec<Passport>, Box<dyn Error>> {
+        parse_records(&input)
+            .filter_map(|record| record.ok())
+            .filter(|record| InvalidPassport::check(&schema, record.index, &record.fields).is_none())
+            .map(|record| Ok(Passport::try_from(&record)?))
+            .collect()
+    };
+
+    for arg in args.iter() {
+        match arg.as_str() {
+            "--report" => print!("{}", report),
+            "--report=json" => println!("{}", report.to_json()?),
+            "--export=batch" => print!("{}", passport::to_batch(&valid_passports()?)),
+            "--export=json" => println!("{}", serde_json::to_string_pretty(&valid_passports()?)?),
+            _ => {},
+        }
+    }
 
     Ok(())
 }
-
-
-
-fn is_field_valid(key: &str, value: &str) -> bool {
-    match key {
-        "byr" => match value.parse::<usize>() {
-            Ok(birth_year) => birth_year >= 1920 && birth_year <= 2002,
-            Err(_) => false,
-        },
-        "iyr" => match value.parse::<usize>() {
-            Ok(issue_year) => issue_year >= 2010 && issue_year <= 2020,
-            Err(_) => false,
-        },
-        "eyr" => match value.parse::<usize>() {
-            Ok(expiration_year) => expiration_year >= 2020 && expiration_year <= 2030,
-            Err(_) => false,
-        },
-        "hgt" => {
-            let unit = &value[value.len()-2..];
-            let height: i32 = (&value[..value.len()-2]).parse::<i32>().unwrap_or(-1);
-
-            match unit {
-                "cm" => height >= 150 && height <= 193,
-                "in" => height >= 59 && height <= 76,
-                _ => false,
-            }
-        },
-        "hcl" => value.starts_with("#") && value.chars().skip(1).all(|c| c.is_ascii_hexdigit()),
-        "ecl" => match value {
-            "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth" => true,
-            _ => false,
-        },
-        "pid" => value.len() == 9 && value.parse::<usize>().is_ok(),
-        "cid" => true,
-        _ => unreachable!(),
-    }
-}
@@ -0,0 +1,271 @@
+use std::{convert::TryFrom, error::Error, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::record::Record;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Passport {
+    pub byr: u16,
+    pub iyr: u16,
+    pub eyr: u16,
+    pub hgt: Height,
+    pub hcl: Rgb,
+    pub ecl: EyeColour,
+    pub pid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Height {
+    Cm(u32),
+    In(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EyeColour {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PassportError {
+    MissingField(&'static str),
+    InvalidField { field: &'static str, value: String },
+}
+
+impl<'a> TryFrom<&Record<'a>> for Passport {
+    type Error = PassportError;
+
+    fn try_from(record: &Record<'a>) -> Result<Self, Self::Error> {
+        let field = |key: &'static str| record.fields.get(key).copied().ok_or(PassportError::MissingField(key));
+
+        Ok(Self {
+            byr: parse_field("byr", field("byr")?)?,
+            iyr: parse_field("iyr", field("iyr")?)?,
+            eyr: parse_field("eyr", field("eyr")?)?,
+            hgt: parse_field("hgt", field("hgt")?)?,
+            hcl: parse_field("hcl", field("hcl")?)?,
+            ecl: parse_field("ecl", field("ecl")?)?,
+            pid: field("pid")?.to_string(),
+            cid: record.fields.get("cid").map(|value| value.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "byr:{} iyr:{} eyr:{} hgt:{} hcl:{} ecl:{} pid:{}",
+            self.byr, self.iyr, self.eyr, self.hgt, self.hcl, self.ecl, self.pid
+        )?;
+        if let Some(cid) = &self.cid {
+            write!(f, " cid:{}", cid)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_field<T: FromStr>(field: &'static str, value: &str) -> Result<T, PassportError> {
+    value.parse().map_err(|_| PassportError::InvalidField { field, value: value.to_string() })
+}
+
+pub fn to_batch(passports: &[Passport]) -> String {
+    passports.iter().map(|passport| format!("{}\n", passport)).collect::<Vec<String>>().join("\n")
+}
+
+impl FromStr for Height {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid height: {:?}", input);
+
+        if let Some(value) = input.strip_suffix("cm") {
+            Ok(Height::Cm(value.parse().map_err(|_| invalid())?))
+        } else if let Some(value) = input.strip_suffix("in") {
+            Ok(Height::In(value.parse().map_err(|_| invalid())?))
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Height::Cm(value) => write!(f, "{}cm", value),
+            Height::In(value) => write!(f, "{}in", value),
+        }
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid colour: {:?}", input);
+        let hex = input.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit())).ok_or_else(invalid)?;
+        let channel = |range| u8::from_str_radix(&hex[range], 16).map_err(|_| invalid());
+
+        Ok(Rgb { r: channel(0..2)?, g: channel(2..4)?, b: channel(4..6)? })
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for EyeColour {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "amb" => Ok(EyeColour::Amb),
+            "blu" => Ok(EyeColour::Blu),
+            "brn" => Ok(EyeColour::Brn),
+            "gry" => Ok(EyeColour::Gry),
+            "grn" => Ok(EyeColour::Grn),
+            "hzl" => Ok(EyeColour::Hzl),
+            "oth" => Ok(EyeColour::Oth),
+            _ => Err(format!("invalid eye colour: {:?}", input)),
+        }
+    }
+}
+
+impl fmt::Display for EyeColour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EyeColour::Amb => "amb",
+            EyeColour::Blu => "blu",
+            EyeColour::Brn => "brn",
+            EyeColour::Gry => "gry",
+            EyeColour::Grn => "grn",
+            EyeColour::Hzl => "hzl",
+            EyeColour::Oth => "oth",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<String> for Height {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Height> for String {
+    fn from(height: Height) -> Self {
+        height.to_string()
+    }
+}
+
+impl TryFrom<String> for Rgb {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Rgb> for String {
+    fn from(colour: Rgb) -> Self {
+        colour.to_string()
+    }
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassportError::MissingField(field) => write!(f, "missing field {}", field),
+            PassportError::InvalidField { field, value } => write!(f, "invalid {}: {:?}", field, value),
+        }
+    }
+}
+
+impl Error for PassportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::parse_records;
+
+    const BATCH: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n\nhcl:#ae17e1 iyr:2013\neyr:2024\necl:brn pid:760753108 byr:1931\nhgt:60in\n\nhcl:#cfa07d eyr:2025 pid:166559648\niyr:2011 ecl:brn hgt:59in\n";
+
+    fn parse_batch(input: &str) -> Vec<Result<Passport, PassportError>> {
+        parse_records(input).map(|record| Passport::try_from(&record.unwrap())).collect()
+    }
+
+    #[test]
+    fn test_typed_fields() {
+        let passports = parse_batch(BATCH);
+        let passport = passports[0].as_ref().unwrap();
+
+        assert_eq!(passport.byr, 1937);
+        assert_eq!(passport.hgt, Height::Cm(183));
+        assert_eq!(passport.hcl, Rgb { r: 0xff, g: 0xff, b: 0xfd });
+        assert_eq!(passport.ecl, EyeColour::Gry);
+        assert_eq!(passport.cid.as_deref(), Some("147"));
+        assert_eq!(passports[1].as_ref().unwrap().hgt, Height::In(60));
+        assert_eq!(passports[2], Err(PassportError::MissingField("byr")));
+    }
+
+    #[test]
+    fn test_batch_round_trip() {
+        let passports: Vec<Passport> = parse_batch(BATCH).into_iter().filter_map(|passport| passport.ok()).collect();
+        let batch = to_batch(&passports);
+
+        assert_eq!(batch, "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:147\n\nbyr:1931 iyr:2013 eyr:2024 hgt:60in hcl:#ae17e1 ecl:brn pid:760753108\n");
+        assert_eq!(parse_batch(&batch).into_iter().collect::<Result<Vec<_>, _>>(), Ok(passports));
+    }
+
+    #[test]
+    fn test_cid_kept_verbatim() {
+        let batch = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:047\n\nbyr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327 cid:north\n";
+        let passports: Vec<Passport> = parse_batch(batch).into_iter().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(passports[0].cid.as_deref(), Some("047"));
+        assert_eq!(passports[1].cid.as_deref(), Some("north"));
+        assert_eq!(to_batch(&passports), batch);
+    }
+
+    #[test]
+    fn test_json_round_trip() -> Result<(), Box<dyn Error>> {
+        let passport = parse_batch(BATCH).remove(0)?;
+        let json = serde_json::to_string(&passport)?;
+
+        assert_eq!(json, r##"{"byr":1937,"iyr":2017,"eyr":2020,"hgt":"183cm","hcl":"#fffffd","ecl":"gry","pid":"860033327","cid":"147"}"##);
+        assert_eq!(serde_json::from_str::<Passport>(&json)?, passport);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let passports = parse_batch("byr:1937 iyr:2017 eyr:2020 hgt:183 hcl:#fffffd ecl:gry pid:860033327\n\nbyr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:fffffd ecl:gry pid:860033327\n");
+
+        assert_eq!(passports[0], Err(PassportError::InvalidField { field: "hgt", value: "183".to_string() }));
+        assert_eq!(passports[1], Err(PassportError::InvalidField { field: "hcl", value: "fffffd".to_string() }));
+        assert_eq!(parse_batch("byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:0123\n")[0].as_ref().map(|passport| passport.pid.as_str()), Ok("0123"));
+        assert!("#+f+f+f".parse::<Rgb>().is_err());
+        assert!(serde_json::from_str::<Rgb>(r##""#+f+f+f""##).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::{collections::HashMap, error::Error, fmt, str::Lines};
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq)]
+pub struct Record<'a> {
+    pub index: usize,
+    pub fields: HashMap<&'a str, &'a str>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "error", rename_all = "snake_case")]
+pub enum RecordError {
+    MissingSeparator { index: usize, token: String },
+    EmptyKey { index: usize, token: String },
+    DuplicateKey { index: usize, key: String },
+}
+
+pub struct Records<'a> {
+    lines: Lines<'a>,
+    index: usize,
+}
+
+pub fn parse_records(input: &str) -> Records<'_> {
+    Records { lines: input.lines(), index: 0 }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lines: Vec<&str> = self.lines.by_ref()
+            .skip_while(|line| line.trim().is_empty())
+            .take_while(|line| !line.trim().is_empty())
+            .collect();
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        Some(Record::parse(index, &lines))
+    }
+}
+
+impl<'a> Record<'a> {
+    fn parse(index: usize, lines: &[&'a str]) -> Result<Self, RecordError> {
+        let mut fields = HashMap::new();
+
+        for token in lines.iter().flat_map(|line| line.split_whitespace()) {
+            let (key, value) = token.split_once(':')
+                .ok_or_else(|| RecordError::MissingSeparator { index, token: token.to_string() })?;
+
+            if key.is_empty() {
+                return Err(RecordError::EmptyKey { index, token: token.to_string() });
+            }
+            if fields.insert(key, value).is_some() {
+                return Err(RecordError::DuplicateKey { index, key: key.to_string() });
+            }
+        }
+
+        Ok(Self { index, fields })
+    }
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::MissingSeparator { index, token } => write!(f, "record #{}: {:?} is not a key:value pair", index, token),
+            RecordError::EmptyKey { index, token } => write!(f, "record #{}: {:?} has an empty key", index, token),
+            RecordError::DuplicateKey { index, key } => write!(f, "record #{}: duplicate key {:?}", index, key),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_lines_and_crlf() {
+        let input = "\r\nbyr:1937 iyr:2017\r\ncid:147\r\n\r\n\r\n  \r\nhcl:#ae17e1\r\n";
+        let records: Vec<_> = parse_records(input).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].fields.get("cid"), Some(&"147"));
+        assert_eq!(records[1].index, 1);
+        assert_eq!(records[1].fields.get("hcl"), Some(&"#ae17e1"));
+    }
+
+    #[test]
+    fn test_malformed_records() {
+        let input = "byr:1937 iyr\n\nbyr:1 byr:2\n\n:5\n\nhgt:\n";
+        let records: Vec<_> = parse_records(input).collect();
+
+        assert_eq!(records[0], Err(RecordError::MissingSeparator { index: 0, token: "iyr".to_string() }));
+        assert_eq!(records[1], Err(RecordError::DuplicateKey { index: 1, key: "byr".to_string() }));
+        assert_eq!(records[2], Err(RecordError::EmptyKey { index: 2, token: ":5".to_string() }));
+        assert_eq!(records[3].as_ref().map(|record| record.fields.get("hgt").cloned()), Ok(Some("")));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use serde::Serialize;
+
+use crate::{record::{Record, RecordError}, schema::Schema};
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub total: usize,
+    pub invalid: Vec<InvalidPassport>,
+    pub malformed: Vec<RecordError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvalidPassport {
+    pub index: usize,
+    pub missing_fields: Vec<String>,
+    pub invalid_fields: Vec<FieldViolation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldViolation {
+    pub field: String,
+    pub value: String,
+    pub rule: String,
+}
+
+impl Report {
+    pub fn new<'a>(schema: &Schema, records: impl IntoIterator<Item = Result<Record<'a>, RecordError>>) -> Self {
+        let mut report = Self { total: 0, invalid: Vec::new(), malformed: Vec::new() };
+
+        for record in records {
+            report.total += 1;
+            match record {
+                Ok(record) => report.invalid.extend(InvalidPassport::check(schema, record.index, &record.fields)),
+                Err(error) => report.malformed.push(error),
+            }
+        }
+
+        report
+    }
+
+    pub fn num_valid(&self) -> usize {
+        self.total - self.invalid.len() - self.malformed.len()
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl InvalidPassport {
+    pub fn check(schema: &Schema, index: usize, passport: &HashMap<&str, &str>) -> Option<Self> {
+        let missing_fields: Vec<String> = schema.required_fields()
+            .filter(|key| !passport.contains_key(key))
+            .map(String::from)
+            .collect();
+
+        let mut invalid_fields: Vec<FieldViolation> = passport.iter()
+            .filter(|(key, value)| !schema.is_field_valid(key, value))
+            .map(|(key, value)| FieldViolation {
+                field: key.to_string(),
+                value: value.to_string(),
+                rule: schema.fields.get(*key).map_or_else(|| "field defined in schema".to_string(), |spec| spec.rule.to_string()),
+            })
+            .collect();
+        invalid_fields.sort_by(|a, b| a.field.cmp(&b.field));
+
+        if missing_fields.is_empty() && invalid_fields.is_empty() {
+            return None;
+        }
+
+        Some(Self { index, missing_fields, invalid_fields })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} of {} passports invalid", self.invalid.len() + self.malformed.len(), self.total)?;
+
+        for error in self.malformed.iter() {
+            writeln!(f, "{}", error)?;
+        }
+
+        for passport in self.invalid.iter() {
+            writeln!(f, "passport #{}", passport.index)?;
+            if !passport.missing_fields.is_empty() {
+                writeln!(f, "  missing: {}", passport.missing_fields.join(", "))?;
+            }
+            for violation in passport.invalid_fields.iter() {
+                writeln!(f, "  {}: {:?} (expected {})", violation.field, violation.value, violation.rule)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::parse_records;
+
+    #[test]
+    fn test_report() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_toml(r#"
+            [fields.byr]
+            required = true
+            type = "range"
+            min = 1920
+            max = 2002
+
+            [fields.ecl]
+            required = true
+            type = "enum"
+            values = ["amb", "blu"]
+        "#)?;
+
+        let input = "byr:1930 ecl:amb\n\nbyr:1900\n\nbyr:1930 ecl:amb xyz:1\n\nbyr:1930 ecl:amb ecl:blu\n";
+        let report = Report::new(&schema, parse_records(input));
+
+        assert_eq!(report.total, 4);
+        assert_eq!(report.num_valid(), 1);
+        assert_eq!(report.malformed, vec![RecordError::DuplicateKey { index: 3, key: "ecl".to_string() }]);
+        assert_eq!(report.invalid[0].index, 1);
+        assert_eq!(report.invalid[0].missing_fields, vec!["ecl"]);
+        assert_eq!(report.invalid[0].invalid_fields[0].rule, "integer in 1920..=2002");
+        assert_eq!(report.invalid[1].index, 2);
+        assert_eq!(report.invalid[1].invalid_fields[0].rule, "field defined in schema");
+
+        assert_eq!(
+            report.to_string(),
+            "3 of 4 passports invalid\nrecord #3: duplicate key \"ecl\"\npassport #1\n  missing: ecl\n  byr: \"1900\" (expected integer in 1920..=2002)\npassport #2\n  xyz: \"1\" (expected field defined in schema)\n"
+        );
+        Ok(())
+    }
+}
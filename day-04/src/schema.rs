@@ -0,0 +1,160 @@
+use std::{collections::BTreeMap, error::Error, fmt, fs::read_to_string, path::Path};
+
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Deserialize)]
+pub struct Schema {
+    pub fields: BTreeMap<String, FieldSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldSpec {
+    #[serde(default)]
+    pub required: bool,
+    #[serde(flatten)]
+    pub rule: Rule,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Rule {
+    Any,
+    Range { min: i64, max: i64 },
+    UnitRange { units: BTreeMap<String, (i64, i64)> },
+    Regex {
+        #[serde(deserialize_with = "deserialize_anchored_regex")]
+        pattern: Regex,
+    },
+    Enum { values: Vec<String> },
+}
+
+impl Schema {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let input = read_to_string(path)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&input),
+            Some("json") => Self::from_json(&input),
+            _ => Err(format!("unsupported schema format: {}", path.display()).into()),
+        }
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self> {
+        Ok(toml::from_str(input)?)
+    }
+
+    pub fn from_json(input: &str) -> Result<Self> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    pub fn required_fields(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().filter(|(_, spec)| spec.required).map(|(key, _)| key.as_str())
+    }
+
+    pub fn is_field_valid(&self, key: &str, value: &str) -> bool {
+        self.fields.get(key).is_some_and(|spec| spec.rule.matches(value))
+    }
+}
+
+impl Rule {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            Rule::Any => true,
+            Rule::Range { min, max } => value.parse::<i64>().is_ok_and(|number| (*min..=*max).contains(&number)),
+            Rule::UnitRange { units } => units.iter().any(|(unit, (min, max))| {
+                value.strip_suffix(unit.as_str())
+                    .and_then(|number| number.parse::<i64>().ok())
+                    .is_some_and(|number| (*min..=*max).contains(&number))
+            }),
+            Rule::Regex { pattern } => pattern.is_match(value),
+            Rule::Enum { values } => values.iter().any(|allowed| allowed == value),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Any => write!(f, "any value"),
+            Rule::Range { min, max } => write!(f, "integer in {}..={}", min, max),
+            Rule::UnitRange { units } => {
+                let ranges: Vec<String> = units.iter().map(|(unit, (min, max))| format!("{}..={}{}", min, max, unit)).collect();
+                write!(f, "one of {}", ranges.join(", "))
+            },
+            Rule::Regex { pattern } => write!(f, "value matching /{}/", pattern.as_str()),
+            Rule::Enum { values } => write!(f, "one of [{}]", values.join(", ")),
+        }
+    }
+}
+
+fn deserialize_anchored_regex<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&format!("^(?:{})$", pattern)).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r##"
+        [fields.byr]
+        required = true
+        type = "range"
+        min = 1920
+        max = 2002
+
+        [fields.hgt]
+        required = true
+        type = "unit_range"
+        units = { cm = [150, 193], in = [59, 76] }
+
+        [fields.hcl]
+        required = true
+        type = "regex"
+        pattern = "#[0-9a-f]{6}"
+
+        [fields.ecl]
+        required = true
+        type = "enum"
+        values = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
+
+        [fields.cid]
+        type = "any"
+    "##;
+
+    #[test]
+    fn test_rules() -> Result<()> {
+        let schema = Schema::from_toml(SCHEMA)?;
+
+        assert!(schema.is_field_valid("byr", "2002"));
+        assert!(!schema.is_field_valid("byr", "2003"));
+        assert!(schema.is_field_valid("hgt", "60in"));
+        assert!(schema.is_field_valid("hgt", "190cm"));
+        assert!(!schema.is_field_valid("hgt", "190in"));
+        assert!(!schema.is_field_valid("hgt", "190"));
+        assert!(!schema.is_field_valid("hgt", "m"));
+        assert!(schema.is_field_valid("hcl", "#123abc"));
+        assert!(!schema.is_field_valid("hcl", "#123abz"));
+        assert!(!schema.is_field_valid("hcl", "123abc"));
+        assert!(schema.is_field_valid("ecl", "brn"));
+        assert!(!schema.is_field_valid("ecl", "wat"));
+        assert!(schema.is_field_valid("cid", "anything"));
+        assert!(!schema.is_field_valid("xyz", "1"));
+
+        assert_eq!(schema.required_fields().collect::<Vec<_>>(), vec!["byr", "ecl", "hcl", "hgt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_schema() -> Result<()> {
+        let schema = Schema::from_json(r#"{"fields": {"pid": {"required": true, "type": "regex", "pattern": "[0-9]{9}"}}}"#)?;
+
+        assert!(schema.is_field_valid("pid", "000000001"));
+        assert!(!schema.is_field_valid("pid", "0123456789"));
+        Ok(())
+    }
+}
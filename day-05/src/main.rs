@@ -1,31 +1,167 @@
-use std::{error::Error, fs::File, io::BufReader, io::prelude::*};
+mod seat_map;
 
-const ROWS: u32 = 256;
-const COLUMNS: u32 = 8;
+use std::{env, error::Error, fmt, fs::read_to_string, str::FromStr};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut reader = BufReader::new(File::open("input/input1.txt")?);
-    let mut input = String::new();
-    let _ = reader.read_to_string(&mut input);
+use seat_map::SeatMap;
 
-    let mut seat_ids: Vec<u32> = input.lines().map(|boarding_pass| gen_seat_id(boarding_pass)).collect();
-    seat_ids.sort();
-    
-    let highest_seat_id = seat_ids.last().ok_or("empty input")?;
-    println!("Part 1: {:#?}", highest_seat_id);
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
-    let (next_seat_id, _) = seat_ids.windows(2).enumerate().find(|(_, pair)| pair[1] - pair[0] == 2).ok_or("seat not found")?;
-    let my_seat_id = next_seat_id - 1;
-    println!("Part 2: {:#?}", my_seat_id);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Geometry {
+    row_bits: u32,
+    column_bits: u32,
+}
 
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BoardingPass {
+    row: u32,
+    column: u32,
+    geometry: Geometry,
+}
+
+#[derive(Debug, PartialEq)]
+enum PassError {
+    InvalidLength { expected: usize, found: usize },
+    InvalidCharacter { position: usize, found: char },
+    OutOfRange { row: u32, column: u32 },
+    UnsupportedGeometry(Geometry),
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Self { row_bits: 7, column_bits: 3 }
+    }
+}
+
+impl Geometry {
+    fn new(row_bits: u32, column_bits: u32) -> std::result::Result<Self, PassError> {
+        let geometry = Self { row_bits, column_bits };
+        match row_bits.checked_add(column_bits) {
+            Some(bits) if bits <= u32::BITS => Ok(geometry),
+            _ => Err(PassError::UnsupportedGeometry(geometry)),
+        }
+    }
+
+    fn rows(&self) -> u64 {
+        1 << self.row_bits
+    }
+
+    fn columns(&self) -> u64 {
+        1 << self.column_bits
+    }
+
+    fn code_length(&self) -> usize {
+        (self.row_bits + self.column_bits) as usize
+    }
+}
+
+impl BoardingPass {
+    fn new(row: u32, column: u32, geometry: Geometry) -> std::result::Result<Self, PassError> {
+        if row as u64 >= geometry.rows() || column as u64 >= geometry.columns() {
+            return Err(PassError::OutOfRange { row, column });
+        }
+        Ok(Self { row, column, geometry })
+    }
+
+    fn from_id(id: u32, geometry: Geometry) -> std::result::Result<Self, PassError> {
+        let columns = geometry.columns();
+        Self::new((id as u64 / columns) as u32, (id as u64 % columns) as u32, geometry)
+    }
+
+    fn decode(code: &str, geometry: Geometry) -> std::result::Result<Self, PassError> {
+        let found = code.chars().count();
+        if found != geometry.code_length() {
+            return Err(PassError::InvalidLength { expected: geometry.code_length(), found });
+        }
+
+        let (mut row, mut column) = (0, 0);
+        for (position, c) in code.chars().enumerate() {
+            let (value, bit) = match (position < geometry.row_bits as usize, c) {
+                (true, 'F') => (&mut row, 0),
+                (true, 'B') => (&mut row, 1),
+                (false, 'L') => (&mut column, 0),
+                (false, 'R') => (&mut column, 1),
+                (_, found) => return Err(PassError::InvalidCharacter { position, found }),
+            };
+            *value = (*value << 1) | bit;
+        }
+
+        Self::new(row, column, geometry)
+    }
+
+    fn encode(&self) -> String {
+        let row_bits = (0..self.geometry.row_bits).rev().map(|bit| if self.row >> bit & 1 == 1 { 'B' } else { 'F' });
+        let column_bits = (0..self.geometry.column_bits).rev().map(|bit| if self.column >> bit & 1 == 1 { 'R' } else { 'L' });
+
+        row_bits.chain(column_bits).collect()
+    }
+
+    fn id(&self) -> u32 {
+        ((self.row as u64) << self.geometry.column_bits | self.column as u64) as u32
+    }
+}
+
+impl FromStr for BoardingPass {
+    type Err = PassError;
+
+    fn from_str(code: &str) -> std::result::Result<Self, Self::Err> {
+        Self::decode(code, Geometry::default())
+    }
+}
+
+impl fmt::Display for BoardingPass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
 }
 
-fn gen_seat_id(boarding_pass: &str) -> u32 {
-    let row_encoding = u32::from_str_radix(boarding_pass[0..7].replace("B", "1").replace("F", "0").as_str(), 2).unwrap();
-    let column_encoding = u32::from_str_radix(boarding_pass[7..].replace("R", "1").replace("L", "0").as_str(), 2).unwrap();
-    
-    ((ROWS-1) & row_encoding) * COLUMNS + ((COLUMNS-1) & column_encoding)
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassError::InvalidLength { expected, found } => write!(f, "expected {} characters, found {}", expected, found),
+            PassError::InvalidCharacter { position, found } => write!(f, "unexpected {:?} at position {}", found, position),
+            PassError::OutOfRange { row, column } => write!(f, "seat ({}, {}) is outside the plane", row, column),
+            PassError::UnsupportedGeometry(geometry) => write!(f, "{} row bits and {} column bits exceed {} bits", geometry.row_bits, geometry.column_bits, u32::BITS),
+        }
+    }
+}
+
+impl Error for PassError {}
+
+fn main() -> Result<()> {
+    let input = read_to_string("input/input1.txt")?;
+
+    let (flags, args): (Vec<String>, Vec<String>) = env::args().skip(1).partition(|arg| arg.starts_with("--"));
+    let args: Vec<u32> = args.iter().map(|arg| arg.parse()).collect::<std::result::Result<_, _>>()?;
+    let geometry = match args[..] {
+        [] => Geometry::default(),
+        [row_bits, column_bits] => Geometry::new(row_bits, column_bits)?,
+        _ => return Err("usage: day-05 [--report] [<row bits> <column bits>]".into()),
+    };
+
+    let passes: Vec<BoardingPass> = input.lines()
+        .map(|code| BoardingPass::decode(code, geometry))
+        .collect::<std::result::Result<_, _>>()?;
+
+    let highest_seat_id = passes.iter().map(BoardingPass::id).max().ok_or("empty input")?;
+    println!("Part 1: {:#?}", highest_seat_id);
+
+    let seat_map = SeatMap::new(geometry, passes)?;
+    let my_seat = match seat_map.gaps()[..] {
+        [seat] => Ok(seat),
+        [] => Err("seat not found"),
+        _ => Err("more than one candidate seat"),
+    };
+    if let Ok(seat) = my_seat {
+        println!("Part 2: {:#?} ({})", seat.id(), seat);
+    }
+
+    if flags.iter().any(|flag| flag == "--report") {
+        print!("\n{}", seat_map.report());
+    }
+
+    my_seat?;
+    Ok(())
 }
 
 
@@ -34,10 +170,41 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_id_generation() {
-        assert_eq!(gen_seat_id("FBFBBFFRLR"), 357);
-        assert_eq!(gen_seat_id("BFFFBBFRRR"), 567);
-        assert_eq!(gen_seat_id("FFFBBBFRRR"), 119);
-        assert_eq!(gen_seat_id("BBFFBBFRLL"), 820);
+    fn test_id_generation() -> Result<()> {
+        assert_eq!("FBFBBFFRLR".parse::<BoardingPass>()?.id(), 357);
+        assert_eq!("BFFFBBFRRR".parse::<BoardingPass>()?.id(), 567);
+        assert_eq!("FFFBBBFRRR".parse::<BoardingPass>()?.id(), 119);
+        assert_eq!("BBFFBBFRLL".parse::<BoardingPass>()?.id(), 820);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoding() -> Result<()> {
+        let pass = BoardingPass::from_id(357, Geometry::default())?;
+        assert_eq!((pass.row, pass.column), (44, 5));
+        assert_eq!(pass.to_string(), "FBFBBFFRLR");
+
+        let geometry = Geometry::new(3, 2)?;
+        let pass = BoardingPass::decode("BFBLR", geometry)?;
+        assert_eq!((pass.row, pass.column, pass.id()), (5, 1, 21));
+        assert_eq!(BoardingPass::new(5, 1, geometry)?.encode(), "BFBLR");
+
+        let geometry = Geometry::new(14, 10)?;
+        let pass = BoardingPass::decode("BFFFFFFFFFFFFBRLLLLLLLLR", geometry)?;
+        assert_eq!((pass.row, pass.column, pass.id()), (8193, 513, 8193 * 1024 + 513));
+        assert_eq!(BoardingPass::from_id(u32::MAX, Geometry::new(0, 32)?)?.encode(), "R".repeat(32));
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_passes() {
+        assert_eq!("FBFBBFFRL".parse::<BoardingPass>(), Err(PassError::InvalidLength { expected: 10, found: 9 }));
+        assert_eq!("FBFBBFRRLR".parse::<BoardingPass>(), Err(PassError::InvalidCharacter { position: 6, found: 'R' }));
+        assert_eq!("FBFBBFFRLX".parse::<BoardingPass>(), Err(PassError::InvalidCharacter { position: 9, found: 'X' }));
+        assert_eq!(BoardingPass::new(128, 0, Geometry::default()), Err(PassError::OutOfRange { row: 128, column: 0 }));
+        assert!(Geometry::new(30, 3).is_err());
+        assert!(Geometry::new(u32::MAX, 1).is_err());
+        assert!(Geometry::new(16, 16).is_ok());
+        assert!(Geometry::new(16, 17).is_err());
     }
 }
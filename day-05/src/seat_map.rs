@@ -0,0 +1,148 @@
+use std::{collections::BTreeSet, fmt};
+
+use crate::{BoardingPass, Geometry, Result};
+
+// one flag per seat, so keep the map to about a million seats
+const MAX_SEAT_BITS: u32 = 20;
+
+pub struct SeatMap {
+    geometry: Geometry,
+    occupied: Vec<bool>,
+    duplicates: BTreeSet<u32>,
+}
+
+impl SeatMap {
+    pub fn new(geometry: Geometry, passes: impl IntoIterator<Item = BoardingPass>) -> Result<Self> {
+        if geometry.code_length() > MAX_SEAT_BITS as usize {
+            return Err(format!("a {}-bit plane is too large for the seat map (limit {} bits)", geometry.code_length(), MAX_SEAT_BITS).into());
+        }
+
+        let mut occupied = vec![false; (geometry.rows() * geometry.columns()) as usize];
+        let mut duplicates = BTreeSet::new();
+
+        for pass in passes {
+            let seat = &mut occupied[pass.id() as usize];
+            if *seat {
+                duplicates.insert(pass.id());
+            }
+            *seat = true;
+        }
+
+        Ok(Self { geometry, occupied, duplicates })
+    }
+
+    fn is_occupied(&self, id: u32) -> bool {
+        self.occupied.get(id as usize).copied().unwrap_or(false)
+    }
+
+    fn is_row_empty(&self, row: u32) -> bool {
+        let columns = self.geometry.columns() as usize;
+        let start = row as usize * columns;
+        self.occupied[start..start + columns].iter().all(|seat| !seat)
+    }
+
+    pub fn gaps(&self) -> Vec<BoardingPass> {
+        (1..self.occupied.len() as u32)
+            .filter(|id| !self.is_occupied(*id) && self.is_occupied(id - 1) && self.is_occupied(id + 1))
+            .map(|id| BoardingPass::from_id(id, self.geometry).expect("seat id within the plane"))
+            .collect()
+    }
+
+    pub fn missing_front_rows(&self) -> Vec<u32> {
+        (0..self.geometry.rows() as u32).take_while(|row| self.is_row_empty(*row)).collect()
+    }
+
+    pub fn missing_back_rows(&self) -> Vec<u32> {
+        let mut rows: Vec<u32> = (0..self.geometry.rows() as u32).rev().take_while(|row| self.is_row_empty(*row)).collect();
+        rows.reverse();
+        rows
+    }
+
+    pub fn duplicates(&self) -> Vec<BoardingPass> {
+        self.duplicates.iter()
+            .map(|id| BoardingPass::from_id(*id, self.geometry).expect("seat id within the plane"))
+            .collect()
+    }
+
+    pub fn report(&self) -> String {
+        let format_passes = |passes: Vec<BoardingPass>| {
+            passes.iter().map(|pass| format!("{} ({})", pass.id(), pass)).collect::<Vec<String>>().join(", ")
+        };
+        let format_rows = |rows: Vec<u32>| rows.iter().map(u32::to_string).collect::<Vec<String>>().join(", ");
+
+        format!(
+            "Gaps: {}\nMissing front rows: {}\nMissing back rows: {}\nDuplicate passes: {}\n\n{}",
+            format_passes(self.gaps()),
+            format_rows(self.missing_front_rows()),
+            format_rows(self.missing_back_rows()),
+            format_passes(self.duplicates()),
+            self,
+        )
+    }
+}
+
+impl fmt::Display for SeatMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gaps: BTreeSet<u32> = self.gaps().iter().map(BoardingPass::id).collect();
+        let columns = self.geometry.columns() as u32;
+        let aisle = columns / 2;
+
+        for row in 0..self.geometry.rows() as u32 {
+            write!(f, "{:>4} ", row)?;
+            for column in 0..columns {
+                if column == aisle {
+                    write!(f, " ")?;
+                }
+
+                let id = row * columns + column;
+                let cell = if self.duplicates.contains(&id) {
+                    '!'
+                } else if self.is_occupied(id) {
+                    '#'
+                } else if gaps.contains(&id) {
+                    'O'
+                } else {
+                    '.'
+                };
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat_map(geometry: Geometry, ids: &[u32]) -> SeatMap {
+        SeatMap::new(geometry, ids.iter().map(|id| BoardingPass::from_id(*id, geometry).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_analysis() {
+        let geometry = Geometry::new(3, 2).unwrap();
+        let map = seat_map(geometry, &[4, 5, 7, 8, 9, 11, 12, 13, 13, 14, 15]);
+
+        assert_eq!(map.gaps().iter().map(BoardingPass::id).collect::<Vec<_>>(), vec![6, 10]);
+        assert_eq!(map.missing_front_rows(), vec![0]);
+        assert_eq!(map.missing_back_rows(), vec![4, 5, 6, 7]);
+        assert_eq!(map.duplicates().iter().map(BoardingPass::id).collect::<Vec<_>>(), vec![13]);
+    }
+
+    #[test]
+    fn test_render() {
+        let geometry = Geometry::new(2, 2).unwrap();
+        let map = seat_map(geometry, &[4, 5, 7, 8, 8, 9, 10, 11]);
+
+        assert_eq!(map.to_string(), "   0 .. ..\n   1 ## O#\n   2 !# ##\n   3 .. ..\n");
+    }
+
+    #[test]
+    fn test_too_large() {
+        assert!(SeatMap::new(Geometry::new(12, 8).unwrap(), Vec::new()).is_ok());
+        assert!(SeatMap::new(Geometry::new(14, 10).unwrap(), Vec::new()).is_err());
+    }
+}
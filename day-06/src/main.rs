@@ -1,29 +1,300 @@
-use std::{collections::HashSet, error::Error, collections::HashMap, fs::read_to_string};
+use std::{collections::BTreeMap, env, error::Error, fs::read_to_string, iter::Peekable, str::{Chars, FromStr}};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let input = read_to_string("input/input1.txt")?;
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Answers(u32);
 
-    let questions_count: usize = input.split("\n\n").map(|group_entry| {
-        group_entry.replace("\n", "").chars().collect::<HashSet<char>>().len()
-    }).sum();
+#[derive(Debug)]
+struct Group {
+    people: Vec<Answers>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Query {
+    Union,
+    Intersection,
+    AtLeast(usize),
+    Exactly(usize),
+    Majority,
+    Or(Box<Query>, Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Minus(Box<Query>, Box<Query>),
+}
+
+impl Answers {
+    fn parse(input: &str) -> Result<Self> {
+        input.chars().try_fold(Answers(0), |answers, question| match question {
+            'a'..='z' => Ok(Answers(answers.0 | 1 << (question as u8 - b'a'))),
+            _ => Err(format!("invalid question: {:?}", question).into()),
+        })
+    }
+
+    fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    fn contains(self, question: usize) -> bool {
+        self.0 >> question & 1 == 1
+    }
+}
+
+impl Group {
+    fn parse(input: &str) -> Result<Self> {
+        let people = input.lines().map(Answers::parse).collect::<Result<_>>()?;
+        Ok(Self { people })
+    }
+
+    fn union(&self) -> Answers {
+        Answers(self.people.iter().fold(0, |acc, answers| acc | answers.0))
+    }
+
+    fn intersection(&self) -> Answers {
+        if self.people.is_empty() {
+            return Answers(0);
+        }
+        Answers(self.people.iter().fold(!0 >> 6, |acc, answers| acc & answers.0))
+    }
+
+    fn answered_by(&self, accept: impl Fn(usize) -> bool) -> Answers {
+        Answers((0..26)
+            .filter(|question| accept(self.people.iter().filter(|answers| answers.contains(*question)).count()))
+            .fold(0, |acc, question| acc | 1 << question))
+    }
+}
+
+impl Query {
+    fn eval(&self, group: &Group) -> Answers {
+        match self {
+            Query::Union => group.union(),
+            Query::Intersection => group.intersection(),
+            Query::AtLeast(k) => group.answered_by(|count| count >= *k && count > 0),
+            Query::Exactly(k) => group.answered_by(|count| count == *k && count > 0),
+            Query::Majority => group.answered_by(|count| count * 2 > group.people.len()),
+            Query::Or(lhs, rhs) => Answers(lhs.eval(group).0 | rhs.eval(group).0),
+            Query::And(lhs, rhs) => Answers(lhs.eval(group).0 & rhs.eval(group).0),
+            Query::Minus(lhs, rhs) => Answers(lhs.eval(group).0 & !rhs.eval(group).0),
+        }
+    }
 
-    println!("Part 1: {}", questions_count);
+    fn count(&self, groups: &[Group]) -> usize {
+        groups.iter().map(|group| self.eval(group).count()).sum()
+    }
 
-    let questions_count: usize = input.split("\n\n").map(|group_entry| {
-        let mut question_counts: HashMap<char, usize> = HashMap::new();
-        let mut num_entries: usize = 0;
+    fn parse_expression(input: &mut Peekable<Chars>) -> Result<Self> {
+        let mut query = Self::parse_term(input)?;
 
-        for line in group_entry.lines() {
-            num_entries += 1;
-            for char in line.chars() {
-                *question_counts.entry(char).or_insert(0) += 1
-            }
+        loop {
+            skip_whitespace(input);
+            let combine: fn(Box<Query>, Box<Query>) -> Query = match input.peek() {
+                Some('|') => Query::Or,
+                Some('&') => Query::And,
+                Some('-') => Query::Minus,
+                _ => return Ok(query),
+            };
+            input.next();
+            query = combine(Box::new(query), Box::new(Self::parse_term(input)?));
         }
-        
-        question_counts.iter().filter(|(_, v)| *v == &num_entries).count()
-    }).sum();
+    }
+
+    fn parse_term(input: &mut Peekable<Chars>) -> Result<Self> {
+        skip_whitespace(input);
+
+        if input.peek() == Some(&'(') {
+            input.next();
+            let query = Self::parse_expression(input)?;
+            skip_whitespace(input);
+            return match input.next() {
+                Some(')') => Ok(query),
+                _ => Err("expected ')'".into()),
+            };
+        }
+
+        let mut name = String::new();
+        while let Some(c) = input.next_if(|c| c.is_ascii_alphabetic() || *c == '_') {
+            name.push(c);
+        }
+
+        match name.as_str() {
+            "union" => Ok(Query::Union),
+            "intersection" => Ok(Query::Intersection),
+            "at_least" => Ok(Query::AtLeast(parse_argument(input)?)),
+            "exactly" => Ok(Query::Exactly(parse_argument(input)?)),
+            "majority" => Ok(Query::Majority),
+            "" => Err(format!("expected a query, found {:?}", input.peek()).into()),
+            _ => Err(format!("unknown query: {}", name).into()),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = Box<dyn Error>;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut chars = input.chars().peekable();
+        let query = Self::parse_expression(&mut chars)?;
+
+        match chars.next() {
+            None => Ok(query),
+            Some(c) => Err(format!("unexpected {:?}", c).into()),
+        }
+    }
+}
+
+fn skip_whitespace(input: &mut Peekable<Chars>) {
+    while input.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_argument(input: &mut Peekable<Chars>) -> Result<usize> {
+    skip_whitespace(input);
+    if input.next() != Some('(') {
+        return Err("expected '('".into());
+    }
 
-    println!("Part 2: {}", questions_count);
+    let mut argument = String::new();
+    while let Some(c) = input.next_if(|c| *c != ')') {
+        argument.push(c);
+    }
+    input.next().ok_or("expected ')'")?;
+
+    Ok(argument.trim().parse()?)
+}
+
+#[derive(Debug, PartialEq)]
+struct QuestionStats {
+    question: char,
+    anyone: usize,
+    everyone: usize,
+    majority: usize,
+}
+
+fn question_stats(groups: &[Group]) -> Vec<QuestionStats> {
+    let answers: Vec<(Answers, Answers, Answers)> = groups.iter()
+        .map(|group| (Query::Union.eval(group), Query::Intersection.eval(group), Query::Majority.eval(group)))
+        .collect();
+
+    (0..26).map(|question| {
+        let count = |select: fn(&(Answers, Answers, Answers)) -> Answers| {
+            answers.iter().filter(|group| select(group).contains(question)).count()
+        };
+
+        QuestionStats {
+            question: (b'a' + question as u8) as char,
+            anyone: count(|group| group.0),
+            everyone: count(|group| group.1),
+            majority: count(|group| group.2),
+        }
+    }).collect()
+}
+
+fn group_size_distribution(groups: &[Group]) -> BTreeMap<usize, usize> {
+    let mut distribution = BTreeMap::new();
+    for group in groups {
+        *distribution.entry(group.people.len()).or_insert(0) += 1;
+    }
+    distribution
+}
+
+fn stats_table(groups: &[Group]) -> String {
+    let mut table = format!("{:<8} {:>7} {:>8} {:>8}\n", "question", "anyone", "everyone", "majority");
+    for stats in question_stats(groups) {
+        table += &format!("{:<8} {:>7} {:>8} {:>8}\n", stats.question, stats.anyone, stats.everyone, stats.majority);
+    }
+
+    table += &format!("\n{:<8} {:>7}\n", "size", "groups");
+    for (size, count) in group_size_distribution(groups) {
+        table += &format!("{:<8} {:>7}\n", size, count);
+    }
+    table
+}
+
+fn stats_csv(groups: &[Group]) -> String {
+    let mut csv = String::from("question,anyone,everyone,majority\n");
+    for stats in question_stats(groups) {
+        csv += &format!("{},{},{},{}\n", stats.question, stats.anyone, stats.everyone, stats.majority);
+    }
+
+    csv += "\ngroup_size,groups\n";
+    for (size, count) in group_size_distribution(groups) {
+        csv += &format!("{},{}\n", size, count);
+    }
+    csv
+}
+
+fn main() -> Result<()> {
+    let input = read_to_string("input/input1.txt")?;
+    let groups = input.split("\n\n").map(Group::parse).collect::<Result<Vec<_>>>()?;
+
+    println!("Part 1: {}", "union".parse::<Query>()?.count(&groups));
+    println!("Part 2: {}", "intersection".parse::<Query>()?.count(&groups));
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--stats" => print!("{}", stats_table(&groups)),
+            "--stats=csv" => print!("{}", stats_csv(&groups)),
+            query => println!("{}: {}", query, query.parse::<Query>()?.count(&groups)),
+        }
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+
+    fn count(query: &str) -> Result<usize> {
+        let groups = SAMPLE.split("\n\n").map(Group::parse).collect::<Result<Vec<_>>>()?;
+        Ok(query.parse::<Query>()?.count(&groups))
+    }
+
+    #[test]
+    fn test_parts() -> Result<()> {
+        assert_eq!(count("union")?, 11);
+        assert_eq!(count("intersection")?, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_queries() -> Result<()> {
+        assert_eq!(count("at_least(1)")?, 11);
+        assert_eq!(count("at_least(2)")?, 2);
+        assert_eq!(count("exactly(1)")?, 9);
+        assert_eq!(count("union - intersection")?, 5);
+        assert_eq!(count("exactly(1) & (intersection | at_least(4))")?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_group() -> Result<()> {
+        let groups = "ab\nb\n\n".split("\n\n").map(Group::parse).collect::<Result<Vec<_>>>()?;
+        assert_eq!(groups.len(), 2);
+        assert_eq!("intersection".parse::<Query>()?.count(&groups), 1);
+        assert_eq!("union".parse::<Query>()?.count(&groups), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let groups = SAMPLE.split("\n\n").map(Group::parse).collect::<Result<Vec<_>>>()?;
+        let stats = question_stats(&groups);
+
+        assert_eq!(stats[0], QuestionStats { question: 'a', anyone: 4, everyone: 3, majority: 3 });
+        assert_eq!(stats[1], QuestionStats { question: 'b', anyone: 4, everyone: 2, majority: 2 });
+        assert_eq!(stats[25], QuestionStats { question: 'z', anyone: 0, everyone: 0, majority: 0 });
+        assert_eq!(group_size_distribution(&groups).into_iter().collect::<Vec<_>>(), vec![(1, 2), (2, 1), (3, 1), (4, 1)]);
+        assert!(stats_csv(&groups).starts_with("question,anyone,everyone,majority\na,4,3,3\nb,4,2,2\nc,3,1,1\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        assert!("unoin".parse::<Query>().is_err());
+        assert!("at_least(x)".parse::<Query>().is_err());
+        assert!("(union".parse::<Query>().is_err());
+        assert!("union intersection".parse::<Query>().is_err());
+        assert!(Group::parse("aB").is_err());
+    }
+}
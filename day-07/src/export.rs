@@ -0,0 +1,131 @@
+use std::fmt::Write;
+
+use crate::ruleset::{BagId, BagRuleset};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Highlight {
+    From(BagId),
+    To(BagId),
+}
+
+fn highlighted_bags(ruleset: &BagRuleset, highlight: Option<Highlight>) -> Vec<bool> {
+    let mut highlighted = vec![false; ruleset.len()];
+
+    let (start, neighbours): (BagId, Box<dyn Fn(BagId) -> Vec<BagId>>) = match highlight {
+        None => return highlighted,
+        Some(Highlight::From(bag)) => (bag, Box::new(|bag| ruleset.contents(bag).iter().map(|(inner_bag, _)| *inner_bag).collect())),
+        Some(Highlight::To(bag)) => (bag, Box::new(|bag| ruleset.holders(bag).to_vec())),
+    };
+
+    highlighted[start] = true;
+    let mut stack = vec![start];
+    while let Some(bag) = stack.pop() {
+        for neighbour in neighbours(bag) {
+            if !highlighted[neighbour] {
+                highlighted[neighbour] = true;
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    highlighted
+}
+
+fn edges(ruleset: &BagRuleset) -> impl Iterator<Item = (BagId, BagId, usize)> + '_ {
+    (0..ruleset.len()).flat_map(move |bag| ruleset.contents(bag).iter().map(move |(inner_bag, count)| (bag, *inner_bag, *count)))
+}
+
+pub fn to_dot(ruleset: &BagRuleset, highlight: Option<Highlight>) -> String {
+    let highlighted = highlighted_bags(ruleset, highlight);
+    let quote = |bag: BagId| format!("\"{}\"", ruleset.name(bag).replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut dot = String::from("digraph bags {\n    rankdir=LR;\n");
+    for (bag, is_highlighted) in highlighted.iter().enumerate() {
+        let style = if *is_highlighted { " [color=red, penwidth=2]" } else { "" };
+        let _ = writeln!(dot, "    {}{};", quote(bag), style);
+    }
+    for (bag, inner_bag, count) in edges(ruleset) {
+        let style = if highlighted[bag] && highlighted[inner_bag] { ", color=red, penwidth=2" } else { "" };
+        let _ = writeln!(dot, "    {} -> {} [label=\"{}\"{}];", quote(bag), quote(inner_bag), count, style);
+    }
+    dot.push_str("}\n");
+
+    dot
+}
+
+pub fn to_mermaid(ruleset: &BagRuleset, highlight: Option<Highlight>) -> String {
+    let highlighted = highlighted_bags(ruleset, highlight);
+
+    let mut mermaid = String::from("graph LR\n");
+    for bag in 0..ruleset.len() {
+        let _ = writeln!(mermaid, "    n{}[\"{}\"]", bag, ruleset.name(bag).replace('"', "#quot;"));
+    }
+
+    let mut highlighted_edges = Vec::new();
+    for (edge, (bag, inner_bag, count)) in edges(ruleset).enumerate() {
+        let _ = writeln!(mermaid, "    n{} -->|{}| n{}", bag, count, inner_bag);
+        if highlighted[bag] && highlighted[inner_bag] {
+            highlighted_edges.push(edge.to_string());
+        }
+    }
+
+    let highlighted_bags: Vec<String> = (0..ruleset.len()).filter(|bag| highlighted[*bag]).map(|bag| format!("n{}", bag)).collect();
+    if !highlighted_bags.is_empty() {
+        mermaid.push_str("    classDef highlight stroke:#f00,stroke-width:2px\n");
+        let _ = writeln!(mermaid, "    class {} highlight", highlighted_bags.join(","));
+    }
+    if !highlighted_edges.is_empty() {
+        let _ = writeln!(mermaid, "    linkStyle {} stroke:#f00,stroke-width:2px", highlighted_edges.join(","));
+    }
+
+    mermaid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> BagRuleset {
+        BagRuleset::from_rules(vec![
+            ("light red", vec![("bright white", 1), ("muted yellow", 2)]),
+            ("bright white", vec![("shiny gold", 1)]),
+            ("muted yellow", vec![]),
+            ("shiny gold", vec![]),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_dot() {
+        let ruleset = sample();
+        let dot = to_dot(&ruleset, Some(Highlight::To(ruleset.id("shiny gold").unwrap())));
+
+        assert_eq!(dot, "digraph bags {
+    rankdir=LR;
+    \"light red\" [color=red, penwidth=2];
+    \"bright white\" [color=red, penwidth=2];
+    \"muted yellow\";
+    \"shiny gold\" [color=red, penwidth=2];
+    \"light red\" -> \"bright white\" [label=\"1\", color=red, penwidth=2];
+    \"light red\" -> \"muted yellow\" [label=\"2\"];
+    \"bright white\" -> \"shiny gold\" [label=\"1\", color=red, penwidth=2];
+}
+");
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let ruleset = sample();
+        assert_eq!(to_mermaid(&ruleset, None), "graph LR
+    n0[\"light red\"]
+    n1[\"bright white\"]
+    n2[\"muted yellow\"]
+    n3[\"shiny gold\"]
+    n0 -->|1| n1
+    n0 -->|2| n2
+    n1 -->|1| n3
+");
+
+        let mermaid = to_mermaid(&ruleset, Some(Highlight::From(ruleset.id("bright white").unwrap())));
+        assert!(mermaid.ends_with("    class n1,n3 highlight\n    linkStyle 2 stroke:#f00,stroke-width:2px\n"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod export;
+pub mod parser;
+pub mod ruleset;
@@ -1,56 +1,104 @@
-use std::{collections::HashMap, error::Error, fs::read_to_string};
+use std::{env, error::Error, fs::read_to_string};
+
+use day_07::{export::{self, Highlight}, parser::parse_rules, ruleset::BagRuleset};
 
-type InnerBag = HashMap<String, usize>;
-type BagRuleset = HashMap<String, InnerBag>;
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
 fn main() -> Result<()> {
     let input = read_to_string("input/input1.txt")?;
 
-    let bag_ruleset: BagRuleset = input.lines().map(|line| {
-        let mut part_iter = line.split(" contain ");
-        let bag_name = normalize_bag_name(advance_line_part(&mut part_iter)?);
-        let contents = advance_line_part(&mut part_iter)?;
+    let rules = parse_rules(&input)?;
+    let bag_ruleset = BagRuleset::from_rules(rules)?;
 
-        if contents.contains("no other bags") {
-            return Ok((bag_name, InnerBag::new()));
-        }
-        
-        let inner_bags: InnerBag = contents[..contents.len()-1].split(", ").map(|inner_desc| {
-            let mut inner_desc_iter = inner_desc.splitn(2, " ");
-            let count = advance_line_part(&mut inner_desc_iter)?.parse::<usize>()?;
-            let name = normalize_bag_name(advance_line_part(&mut inner_desc_iter)?);
-            Ok((name, count))
-        }).collect::<Result<InnerBag>>()?;
-
-        Ok((bag_name, inner_bags))
-    }).collect::<Result<BagRuleset>>()?;
-
-    let num_shiny_gold_bag_holders = bag_ruleset.values().filter(|inner_bag| contains_bag(&bag_ruleset, inner_bag, "shiny gold")).count();
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => {},
+        Some("bags") => return run_bags_command(&bag_ruleset, &args[1..]),
+        Some(_) => return Err(USAGE.into()),
+    }
+
+    let shiny_gold = bag_ruleset.id("shiny gold").ok_or("shiny gold bag missing")?;
+
+    let num_shiny_gold_bag_holders = bag_ruleset.all_holders(shiny_gold, None).len();
     println!("Part 1: {}", num_shiny_gold_bag_holders);
 
-    let bags_within_shiny_gold_bag = bag_count(&bag_ruleset, bag_ruleset.get("shiny gold").ok_or("shiny gold bag missing")?);
+    let bags_within_shiny_gold_bag = bag_ruleset.total_contents(shiny_gold, None);
     println!("Part 2: {}", bags_within_shiny_gold_bag);
 
     Ok(())
 }
 
-fn contains_bag(bag_ruleset: &BagRuleset, inner_bag: &InnerBag, target: &str) -> bool {
-    inner_bag.contains_key(target) || 
-        inner_bag.keys().any(|inner_name| {
-            bag_ruleset.contains_key(inner_name) 
-                && contains_bag(bag_ruleset, bag_ruleset.get(inner_name).unwrap(), target)
-        })
-}
+const USAGE: &str = "usage: day-07 bags (holders | contents) <colour> [--depth <n>] [--tree]
+       day-07 bags export (dot | mermaid) [--from <colour> | --to <colour>]";
 
-fn bag_count(bag_ruleset: &BagRuleset, inner_bag: &InnerBag) -> usize {
-    inner_bag.iter().map(|(name, count)| count + count * bag_count(bag_ruleset, bag_ruleset.get(name).unwrap())).sum()
-}
+fn run_bags_command(bag_ruleset: &BagRuleset, args: &[String]) -> Result<()> {
+    let command = match args.first().map(String::as_str) {
+        Some(command @ ("holders" | "contents" | "export")) => command,
+        _ => return Err(USAGE.into()),
+    };
+    let mut depth = None;
+    let mut tree = false;
+    let mut highlight_colour = None;
+    let mut words = Vec::new();
 
-fn normalize_bag_name(bag_name: &str) -> String {
-    bag_name.replace("bags", "").replace("bag", "").trim().to_string()
-}
+    let mut args_iter = args.iter().skip(1).peekable();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--depth" => depth = Some(args_iter.next().ok_or(USAGE)?.parse::<usize>()?),
+            "--tree" => tree = true,
+            "--from" | "--to" => {
+                let mut colour = Vec::new();
+                while let Some(word) = args_iter.next_if(|word| !word.starts_with("--")) {
+                    colour.push(word.as_str());
+                }
+                if colour.is_empty() || highlight_colour.is_some() {
+                    return Err(USAGE.into());
+                }
+                highlight_colour = Some((arg.as_str(), colour.join(" ")));
+            },
+            flag if flag.starts_with("--") => return Err(USAGE.into()),
+            word => words.push(word),
+        }
+    }
 
-fn advance_line_part<'a>(iter: &mut impl Iterator<Item = &'a str>) -> Result<&'a str>  {
-    iter.next().ok_or("invalid line".into())
+    let find_bag = |colour: &str| bag_ruleset.id(colour).ok_or_else(|| format!("unknown bag colour: {:?}", colour));
+
+    if command == "export" {
+        let format = match words.as_slice() {
+            [format] => *format,
+            _ => return Err(USAGE.into()),
+        };
+        let highlight = match highlight_colour {
+            Some(("--from", colour)) => Some(Highlight::From(find_bag(&colour)?)),
+            Some((_, colour)) => Some(Highlight::To(find_bag(&colour)?)),
+            None => None,
+        };
+
+        match format {
+            "dot" => print!("{}", export::to_dot(bag_ruleset, highlight)),
+            "mermaid" => print!("{}", export::to_mermaid(bag_ruleset, highlight)),
+            _ => return Err(USAGE.into()),
+        }
+        return Ok(());
+    }
+
+    if highlight_colour.is_some() || words.is_empty() {
+        return Err(USAGE.into());
+    }
+    let colour = words.join(" ");
+    let bag = find_bag(&colour)?;
+
+    match (command, tree) {
+        ("holders", false) => {
+            let mut holders: Vec<&str> = bag_ruleset.all_holders(bag, depth).into_iter().map(|holder| bag_ruleset.name(holder)).collect();
+            holders.sort_unstable();
+            holders.iter().for_each(|holder| println!("{}", holder));
+            println!("{} bags can hold {} bags", holders.len(), colour);
+        },
+        ("holders", true) => print!("{}", bag_ruleset.holders_tree(bag, depth)),
+        (_, false) => println!("{} bags hold {} bags", colour, bag_ruleset.total_contents(bag, depth)),
+        (_, true) => print!("{}", bag_ruleset.contents_tree(bag, depth)),
+    }
+
+    Ok(())
 }
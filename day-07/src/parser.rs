@@ -0,0 +1,127 @@
+use std::{error::Error, fmt};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1, space0},
+    combinator::{all_consuming, map, map_res, opt, recognize, verify},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated, tuple},
+    Finish, IResult,
+};
+
+pub type InnerBag<'a> = Vec<(&'a str, usize)>;
+pub type Rule<'a> = (&'a str, InnerBag<'a>);
+
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+fn colour(input: &str) -> ParseResult<'_, &str> {
+    let word = verify(take_while1(char::is_alphabetic), |word: &str| word != "bag" && word != "bags");
+    context("bag colour", recognize(separated_list1(char(' '), word)))(input)
+}
+
+fn bag_noun(input: &str) -> ParseResult<'_, &str> {
+    context("\"bag\" or \"bags\"", recognize(pair(tag("bag"), opt(char('s')))))(input)
+}
+
+fn bag(input: &str) -> ParseResult<'_, &str> {
+    terminated(colour, pair(char(' '), bag_noun))(input)
+}
+
+fn count(input: &str) -> ParseResult<'_, usize> {
+    context("bag count", map_res(digit1, str::parse))(input)
+}
+
+fn inner_bag(input: &str) -> ParseResult<'_, (&str, usize)> {
+    map(separated_pair(count, char(' '), bag), |(count, colour)| (colour, count))(input)
+}
+
+fn contents(input: &str) -> ParseResult<'_, InnerBag<'_>> {
+    alt((
+        map(tag("no other bags"), |_| Vec::new()),
+        context("bag list", separated_list1(tag(", "), inner_bag)),
+    ))(input)
+}
+
+fn rule(input: &str) -> ParseResult<'_, Rule<'_>> {
+    let (input, (colour, _, inner_bags, _, _)) = tuple((
+        bag,
+        context("\" contain \"", tag(" contain ")),
+        contents,
+        context("\".\"", char('.')),
+        space0,
+    ))(input)?;
+
+    Ok((input, (colour, inner_bags)))
+}
+
+pub fn parse_rule(line: &str, line_number: usize) -> Result<Rule<'_>, ParseError> {
+    all_consuming(rule)(line).finish().map(|(_, rule)| rule).map_err(|error| {
+        let (remaining, _) = error.errors[0];
+        let expected = error.errors.iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(context.to_string()),
+                _ => None,
+            })
+            .unwrap_or_else(|| "end of line".to_string());
+
+        ParseError { line: line_number, column: line[..line.len() - remaining.len()].chars().count() + 1, expected }
+    })
+}
+
+pub fn parse_rules(input: &str) -> Result<Vec<Rule<'_>>, ParseError> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| parse_rule(line, idx + 1))
+        .collect()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}", self.line, self.column, self.expected)
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(
+            parse_rule("light red bags contain 1 bright white bag, 2 muted yellow bags.", 1),
+            Ok(("light red", vec![("bright white", 1), ("muted yellow", 2)]))
+        );
+        assert_eq!(parse_rule("faded blue bags contain no other bags.", 1), Ok(("faded blue", vec![])));
+        assert_eq!(parse_rule("baggy green bag contain 3 dark baggage bags.", 1), Ok(("baggy green", vec![("dark baggage", 3)])));
+        assert_eq!(parse_rule("líght réd bags contain no other bags.", 1), Ok(("líght réd", vec![])));
+    }
+
+    #[test]
+    fn test_rule_list() {
+        let rules = parse_rules("a b bags contain 1 c d bag.\r\n\r\nc d bags contain no other bags.\r\n").unwrap();
+        assert_eq!(rules, vec![("a b", vec![("c d", 1)]), ("c d", vec![])]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |line| parse_rule(line, 3).unwrap_err();
+
+        assert_eq!(error("light red bags contain 1 bright white bag"), ParseError { line: 3, column: 42, expected: "\".\"".to_string() });
+        assert_eq!(error("light red bags holds 1 bright white bag."), ParseError { line: 3, column: 15, expected: "\" contain \"".to_string() });
+        assert_eq!(error("light red bags contain x bright white bag."), ParseError { line: 3, column: 24, expected: "bag count".to_string() });
+        assert_eq!(error("light red bags contain 1 bright white bag. extra").to_string(), "line 3, column 44: expected end of line");
+        assert_eq!(error("líght réd bags contain 1 bright white bag").column, 42);
+    }
+}
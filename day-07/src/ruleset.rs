@@ -0,0 +1,339 @@
+use std::{collections::HashMap, error::Error, fmt, fmt::Write};
+
+pub type BagId = usize;
+
+#[derive(Debug, Default)]
+pub struct BagRuleset {
+    names: Vec<String>,
+    ids: HashMap<String, BagId>,
+    contents: Vec<Vec<(BagId, usize)>>,
+    holders: Vec<Vec<BagId>>,
+    defined: Vec<bool>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RulesetError {
+    DuplicateDefinition(String),
+    DuplicateContent { bag: String, inner: String },
+    UndefinedBag { bag: String, referenced_by: String },
+    Cycle(Vec<String>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Done,
+}
+
+impl BagRuleset {
+    pub fn from_rules<S: AsRef<str>>(rules: impl IntoIterator<Item = (S, Vec<(S, usize)>)>) -> Result<Self, RulesetError> {
+        let mut ruleset = Self::default();
+
+        for (name, inner_bags) in rules {
+            let bag = ruleset.intern(name.as_ref());
+            if ruleset.defined[bag] {
+                return Err(RulesetError::DuplicateDefinition(ruleset.names[bag].clone()));
+            }
+            ruleset.defined[bag] = true;
+
+            for (inner_name, count) in inner_bags {
+                let inner_bag = ruleset.intern(inner_name.as_ref());
+                if ruleset.contents[bag].iter().any(|(existing, _)| *existing == inner_bag) {
+                    return Err(RulesetError::DuplicateContent { bag: ruleset.names[bag].clone(), inner: ruleset.names[inner_bag].clone() });
+                }
+                ruleset.contents[bag].push((inner_bag, count));
+                ruleset.holders[inner_bag].push(bag);
+            }
+        }
+
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+
+    fn validate(&self) -> Result<(), RulesetError> {
+        if let Some(bag) = (0..self.len()).find(|bag| !self.defined[*bag]) {
+            return Err(RulesetError::UndefinedBag {
+                bag: self.names[bag].clone(),
+                referenced_by: self.names[self.holders[bag][0]].clone(),
+            });
+        }
+
+        let mut visits = vec![Visit::New; self.len()];
+        let mut path = Vec::new();
+        for bag in 0..self.len() {
+            if let Some(cycle) = self.find_cycle(bag, &mut visits, &mut path) {
+                return Err(RulesetError::Cycle(cycle.into_iter().map(|bag| self.names[bag].clone()).collect()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_cycle(&self, bag: BagId, visits: &mut Vec<Visit>, path: &mut Vec<BagId>) -> Option<Vec<BagId>> {
+        match visits[bag] {
+            Visit::Done => return None,
+            Visit::InProgress => {
+                let start = path.iter().position(|visited| *visited == bag).expect("bag on the current path");
+                let mut cycle = path[start..].to_vec();
+                cycle.push(bag);
+                return Some(cycle);
+            },
+            Visit::New => {},
+        }
+
+        visits[bag] = Visit::InProgress;
+        path.push(bag);
+        for (inner_bag, _) in self.contents(bag) {
+            if let Some(cycle) = self.find_cycle(*inner_bag, visits, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        visits[bag] = Visit::Done;
+
+        None
+    }
+
+    fn intern(&mut self, name: &str) -> BagId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = self.names.len();
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.contents.push(Vec::new());
+        self.holders.push(Vec::new());
+        self.defined.push(false);
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<BagId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, bag: BagId) -> &str {
+        &self.names[bag]
+    }
+
+    pub fn contents(&self, bag: BagId) -> &[(BagId, usize)] {
+        &self.contents[bag]
+    }
+
+    pub fn holders(&self, bag: BagId) -> &[BagId] {
+        &self.holders[bag]
+    }
+
+    pub fn all_holders(&self, target: BagId, depth: Option<usize>) -> Vec<BagId> {
+        let mut visited = vec![false; self.len()];
+        let mut level = vec![target];
+
+        for _ in 0..depth.unwrap_or(usize::MAX) {
+            let mut next_level = Vec::new();
+            for bag in level {
+                for holder in self.holders(bag) {
+                    if !visited[*holder] {
+                        visited[*holder] = true;
+                        next_level.push(*holder);
+                    }
+                }
+            }
+
+            if next_level.is_empty() {
+                break;
+            }
+            level = next_level;
+        }
+
+        (0..self.len()).filter(|bag| visited[*bag]).collect()
+    }
+
+    pub fn can_contain(&self, bag: BagId, target: BagId) -> bool {
+        self.can_contain_memoized(bag, target, &mut vec![None; self.len()])
+    }
+
+    fn can_contain_memoized(&self, bag: BagId, target: BagId, memo: &mut Vec<Option<bool>>) -> bool {
+        if let Some(result) = memo[bag] {
+            return result;
+        }
+
+        let result = self.contents(bag).iter()
+            .any(|(inner_bag, _)| *inner_bag == target || self.can_contain_memoized(*inner_bag, target, memo));
+        memo[bag] = Some(result);
+        result
+    }
+
+    pub fn total_contents(&self, bag: BagId, depth: Option<usize>) -> usize {
+        self.total_contents_memoized(bag, depth, &mut HashMap::new())
+    }
+
+    fn total_contents_memoized(&self, bag: BagId, depth: Option<usize>, memo: &mut HashMap<(BagId, Option<usize>), usize>) -> usize {
+        if depth == Some(0) {
+            return 0;
+        }
+        if let Some(total) = memo.get(&(bag, depth)) {
+            return *total;
+        }
+
+        let inner_depth = depth.map(|depth| depth - 1);
+        let total = self.contents(bag).iter()
+            .map(|(inner_bag, count)| count + count * self.total_contents_memoized(*inner_bag, inner_depth, memo))
+            .sum();
+        memo.insert((bag, depth), total);
+        total
+    }
+
+    pub fn contents_tree(&self, bag: BagId, depth: Option<usize>) -> String {
+        let mut tree = format!("{}\n", self.name(bag));
+        self.write_contents_tree(&mut tree, bag, 1, 1, depth);
+        tree
+    }
+
+    fn write_contents_tree(&self, tree: &mut String, bag: BagId, multiplier: usize, level: usize, depth: Option<usize>) {
+        if depth.is_some_and(|depth| level > depth) {
+            return;
+        }
+
+        for (inner_bag, count) in self.contents(bag) {
+            let _ = write!(tree, "{}{} {}", "  ".repeat(level), multiplier * count, self.name(*inner_bag));
+            if multiplier > 1 {
+                let _ = write!(tree, " ({} per {})", count, self.name(bag));
+            }
+            tree.push('\n');
+
+            self.write_contents_tree(tree, *inner_bag, multiplier * count, level + 1, depth);
+        }
+    }
+
+    pub fn holders_tree(&self, target: BagId, depth: Option<usize>) -> String {
+        let mut tree = format!("{}\n", self.name(target));
+        self.write_holders_tree(&mut tree, target, 1, depth);
+        tree
+    }
+
+    fn write_holders_tree(&self, tree: &mut String, bag: BagId, level: usize, depth: Option<usize>) {
+        if depth.is_some_and(|depth| level > depth) {
+            return;
+        }
+
+        for holder in self.holders(bag) {
+            let count = self.contents(*holder).iter().find(|(inner_bag, _)| *inner_bag == bag).map_or(0, |(_, count)| *count);
+            let _ = writeln!(tree, "{}{} (holds {})", "  ".repeat(level), self.name(*holder), count);
+
+            self.write_holders_tree(tree, *holder, level + 1, depth);
+        }
+    }
+}
+
+impl fmt::Display for RulesetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesetError::DuplicateDefinition(bag) => write!(f, "{} bags are defined more than once", bag),
+            RulesetError::DuplicateContent { bag, inner } => write!(f, "{} bags list {} bags more than once", bag, inner),
+            RulesetError::UndefinedBag { bag, referenced_by } => write!(f, "{} bags contain undefined {} bags", referenced_by, bag),
+            RulesetError::Cycle(path) => write!(f, "bags contain themselves: {}", path.join(" -> ")),
+        }
+    }
+}
+
+impl Error for RulesetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(rules: Vec<(&str, Vec<(&str, usize)>)>) -> Result<BagRuleset, RulesetError> {
+        BagRuleset::from_rules(rules)
+    }
+
+    fn sample() -> BagRuleset {
+        build(vec![
+            ("light red", vec![("bright white", 1), ("muted yellow", 2)]),
+            ("dark orange", vec![("bright white", 3), ("muted yellow", 4)]),
+            ("bright white", vec![("shiny gold", 1)]),
+            ("muted yellow", vec![("shiny gold", 2), ("faded blue", 9)]),
+            ("shiny gold", vec![("dark olive", 1), ("vibrant plum", 2)]),
+            ("dark olive", vec![("faded blue", 3), ("dotted black", 4)]),
+            ("vibrant plum", vec![("faded blue", 5), ("dotted black", 6)]),
+            ("faded blue", vec![]),
+            ("dotted black", vec![]),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_holders() {
+        let ruleset = sample();
+        let shiny_gold = ruleset.id("shiny gold").unwrap();
+        let mut holders: Vec<&str> = ruleset.all_holders(shiny_gold, None).into_iter().map(|bag| ruleset.name(bag)).collect();
+        holders.sort_unstable();
+
+        assert_eq!(holders, vec!["bright white", "dark orange", "light red", "muted yellow"]);
+        assert!(ruleset.can_contain(ruleset.id("light red").unwrap(), shiny_gold));
+        assert!(!ruleset.can_contain(ruleset.id("faded blue").unwrap(), shiny_gold));
+
+        let mut direct_holders: Vec<&str> = ruleset.all_holders(shiny_gold, Some(1)).into_iter().map(|bag| ruleset.name(bag)).collect();
+        direct_holders.sort_unstable();
+        assert_eq!(direct_holders, vec!["bright white", "muted yellow"]);
+        assert_eq!(ruleset.all_holders(shiny_gold, Some(0)), vec![]);
+    }
+
+    #[test]
+    fn test_total_contents() {
+        let ruleset = sample();
+        let shiny_gold = ruleset.id("shiny gold").unwrap();
+
+        assert_eq!(ruleset.total_contents(shiny_gold, None), 32);
+        assert_eq!(ruleset.total_contents(shiny_gold, Some(1)), 3);
+        assert_eq!(ruleset.total_contents(shiny_gold, Some(2)), 32);
+        assert_eq!(ruleset.total_contents(ruleset.id("faded blue").unwrap(), None), 0);
+    }
+
+    #[test]
+    fn test_trees() {
+        let ruleset = sample();
+        let shiny_gold = ruleset.id("shiny gold").unwrap();
+
+        assert_eq!(
+            ruleset.contents_tree(shiny_gold, None),
+            "shiny gold\n  1 dark olive\n    3 faded blue\n    4 dotted black\n  2 vibrant plum\n    10 faded blue (5 per vibrant plum)\n    12 dotted black (6 per vibrant plum)\n"
+        );
+        assert_eq!(ruleset.contents_tree(shiny_gold, Some(1)), "shiny gold\n  1 dark olive\n  2 vibrant plum\n");
+        assert_eq!(
+            ruleset.holders_tree(ruleset.id("bright white").unwrap(), None),
+            "bright white\n  light red (holds 1)\n  dark orange (holds 3)\n"
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            build(vec![("a", vec![]), ("b", vec![]), ("a", vec![("b", 1)])]).unwrap_err(),
+            RulesetError::DuplicateDefinition("a".to_string())
+        );
+        assert_eq!(
+            build(vec![("a", vec![("b", 1), ("b", 2)]), ("b", vec![])]).unwrap_err(),
+            RulesetError::DuplicateContent { bag: "a".to_string(), inner: "b".to_string() }
+        );
+        assert_eq!(
+            build(vec![("a", vec![("b", 1)])]).unwrap_err(),
+            RulesetError::UndefinedBag { bag: "b".to_string(), referenced_by: "a".to_string() }
+        );
+        assert_eq!(
+            build(vec![("a", vec![("b", 1)]), ("b", vec![("c", 2)]), ("c", vec![("b", 1)])]).unwrap_err(),
+            RulesetError::Cycle(vec!["b".to_string(), "c".to_string(), "b".to_string()])
+        );
+        assert_eq!(
+            build(vec![("a", vec![("a", 1)])]).unwrap_err().to_string(),
+            "bags contain themselves: a -> a"
+        );
+    }
+}
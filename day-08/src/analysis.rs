@@ -0,0 +1,264 @@
+use std::{convert::TryFrom, fmt::{self, Write}};
+
+use crate::program::{Operation, Program};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    OutOfBoundsJump { pc: usize, target: isize },
+    SelfJump { pc: usize },
+    Unreachable { start: usize, end: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Analysis<'a> {
+    program: &'a Program,
+    pub blocks: Vec<BasicBlock>,
+    pub reachable: Vec<bool>,
+    pub issues: Vec<Issue>,
+}
+
+fn successor(program: &Program, pc: usize) -> isize {
+    let instruction = program.instructions[pc];
+    match instruction.operation {
+        Operation::Jmp => pc as isize + instruction.argument as isize,
+        _ => pc as isize + 1,
+    }
+}
+
+impl Issue {
+    fn pc(&self) -> usize {
+        match *self {
+            Issue::OutOfBoundsJump { pc, .. } | Issue::SelfJump { pc } => pc,
+            Issue::Unreachable { start, .. } => start,
+        }
+    }
+}
+
+impl<'a> Analysis<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        let len = program.len();
+        let in_bounds = |target: isize| (0..=len as isize).contains(&target);
+
+        let mut reachable = vec![false; len];
+        let mut pc = 0;
+        while pc < len && !reachable[pc] {
+            reachable[pc] = true;
+            match successor(program, pc) {
+                target if in_bounds(target) => pc = target as usize,
+                _ => break,
+            }
+        }
+
+        let mut leaders = vec![false; len];
+        if let Some(first) = leaders.first_mut() {
+            *first = true;
+        }
+        let mut issues = Vec::new();
+        for (pc, instruction) in program.instructions.iter().enumerate() {
+            if instruction.operation != Operation::Jmp {
+                continue;
+            }
+
+            let target = successor(program, pc);
+            if !in_bounds(target) {
+                issues.push(Issue::OutOfBoundsJump { pc, target });
+            } else if target as usize == pc {
+                issues.push(Issue::SelfJump { pc });
+            }
+
+            if let Some(leader) = usize::try_from(target).ok().and_then(|target| leaders.get_mut(target)) {
+                *leader = true;
+            }
+            if let Some(leader) = leaders.get_mut(pc + 1) {
+                *leader = true;
+            }
+        }
+
+        let starts: Vec<usize> = leaders.iter().enumerate().filter(|(_, leader)| **leader).map(|(pc, _)| pc).collect();
+        let blocks = starts.iter().enumerate()
+            .map(|(index, start)| BasicBlock { start: *start, end: starts.get(index + 1).copied().unwrap_or(len) })
+            .collect();
+
+        let mut unreachable_start = None;
+        for pc in 0..=len {
+            match (unreachable_start, reachable.get(pc).copied().unwrap_or(true)) {
+                (None, false) => unreachable_start = Some(pc),
+                (Some(start), true) => {
+                    issues.push(Issue::Unreachable { start, end: pc });
+                    unreachable_start = None;
+                },
+                _ => {},
+            }
+        }
+        issues.sort_by_key(Issue::pc);
+
+        Self { program, blocks, reachable, issues }
+    }
+
+    fn block_at(&self, pc: usize) -> Option<usize> {
+        self.blocks.binary_search_by_key(&pc, |block| block.start).ok()
+    }
+
+    pub fn label(&self, pc: usize) -> Option<String> {
+        match self.block_at(pc) {
+            Some(block) => Some(format!("L{}", block)),
+            None if pc == self.program.len() => Some("end".to_string()),
+            None => None,
+        }
+    }
+
+    fn jumps_to_end(&self) -> bool {
+        (0..self.program.len()).any(|pc| self.program.instructions[pc].operation == Operation::Jmp && successor(self.program, pc) == self.program.len() as isize)
+    }
+
+    fn instruction_text(&self, pc: usize) -> String {
+        let instruction = self.program.instructions[pc];
+        let label = usize::try_from(successor(self.program, pc)).ok().and_then(|target| self.label(target));
+        match (instruction.operation, label) {
+            (Operation::Jmp, Some(label)) => format!("jmp {}", label),
+            _ => instruction.to_string(),
+        }
+    }
+
+    fn annotation(&self, pc: usize) -> Option<String> {
+        self.issues.iter().find_map(|issue| match *issue {
+            Issue::OutOfBoundsJump { pc: issue_pc, target } if issue_pc == pc => Some(format!("out of bounds (line {})", target + 1)),
+            Issue::SelfJump { pc: issue_pc } if issue_pc == pc => Some("self-jump".to_string()),
+            _ => None,
+        })
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            let lines: String = (block.start..block.end).map(|pc| format!("{}\\l", self.instruction_text(pc))).collect();
+            let style = if self.reachable[block.start] { "" } else { ", style=dashed, color=gray" };
+            let _ = writeln!(dot, "    L{} [label=\"L{}:\\l{}\"{}];", index, index, lines, style);
+        }
+        dot.push_str("    end [shape=doublecircle];\n");
+
+        let mut out_of_bounds = false;
+        for (index, block) in self.blocks.iter().enumerate() {
+            let last = block.end - 1;
+            let target = successor(self.program, last);
+            match usize::try_from(target).ok().and_then(|target| self.label(target)) {
+                Some(label) if target as usize == last && self.program.instructions[last].operation == Operation::Jmp => {
+                    let _ = writeln!(dot, "    L{} -> {} [color=red];", index, label);
+                },
+                Some(label) => { let _ = writeln!(dot, "    L{} -> {};", index, label); },
+                None => {
+                    out_of_bounds = true;
+                    let _ = writeln!(dot, "    L{} -> out_of_bounds [color=red];", index);
+                },
+            }
+        }
+        if out_of_bounds {
+            dot.push_str("    out_of_bounds [shape=octagon, color=red];\n");
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Issue::OutOfBoundsJump { pc, target } => write!(f, "line {}: jump to line {} is out of bounds", pc + 1, target + 1),
+            Issue::SelfJump { pc } => write!(f, "line {}: jmp +0 jumps to itself", pc + 1),
+            Issue::Unreachable { start, end } if end - start == 1 => write!(f, "line {}: unreachable code", start + 1),
+            Issue::Unreachable { start, end } => write!(f, "lines {}-{}: unreachable code", start + 1, end),
+        }
+    }
+}
+
+impl fmt::Display for Analysis<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, block) in self.blocks.iter().enumerate() {
+            match self.reachable[block.start] {
+                true => writeln!(f, "L{}:", index)?,
+                false => writeln!(f, "{:<16}; unreachable", format!("L{}:", index))?,
+            }
+
+            for pc in block.start..block.end {
+                match self.annotation(pc) {
+                    Some(note) => writeln!(f, "    {:<12}; {}", self.instruction_text(pc), note)?,
+                    None => writeln!(f, "    {}", self.instruction_text(pc))?,
+                }
+            }
+        }
+        if self.jumps_to_end() {
+            writeln!(f, "end:")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_blocks_and_listing() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let analysis = Analysis::new(&program);
+
+        let starts: Vec<usize> = analysis.blocks.iter().map(|block| block.start).collect();
+        assert_eq!(starts, vec![0, 1, 3, 5, 6, 8]);
+        assert_eq!(analysis.issues, vec![Issue::Unreachable { start: 5, end: 6 }, Issue::Unreachable { start: 8, end: 9 }]);
+        assert_eq!(analysis.to_string(), "\
+L0:
+    nop +0
+L1:
+    acc +1
+    jmp L4
+L2:
+    acc +3
+    jmp L1
+L3:             ; unreachable
+    acc -99
+L4:
+    acc +1
+    jmp L2
+L5:             ; unreachable
+    acc +6
+");
+    }
+
+    #[test]
+    fn test_issues() {
+        let program = Program::parse("jmp +0\njmp +5\nacc +1\njmp +1").unwrap();
+        let analysis = Analysis::new(&program);
+
+        assert_eq!(analysis.issues, vec![
+            Issue::SelfJump { pc: 0 },
+            Issue::OutOfBoundsJump { pc: 1, target: 6 },
+            Issue::Unreachable { start: 1, end: 4 },
+        ]);
+        assert_eq!(analysis.issues[2].to_string(), "lines 2-4: unreachable code");
+
+        let listing = analysis.to_string();
+        assert!(listing.contains("    jmp L0      ; self-jump\n"));
+        assert!(listing.contains("    jmp +5      ; out of bounds (line 7)\n"));
+        assert!(listing.ends_with("    jmp end\nend:\n"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let program = Program::parse("jmp +0\njmp +5\nacc +1\njmp +1").unwrap();
+        let dot = Analysis::new(&program).to_dot();
+
+        assert!(dot.contains("    L0 -> L0 [color=red];\n"));
+        assert!(dot.contains("    L1 -> out_of_bounds [color=red];\n"));
+        assert!(dot.contains("    L2 [label=\"L2:\\lacc +1\\ljmp end\\l\", style=dashed, color=gray];\n"));
+        assert!(dot.contains("    L2 -> end;\n"));
+    }
+}
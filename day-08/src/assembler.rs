@@ -0,0 +1,171 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use crate::{analysis::Analysis, program::{Instruction, Operation, Program}};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub message: String,
+}
+
+struct Statement<'a> {
+    line: usize,
+    mnemonic: &'a str,
+    operand: &'a str,
+}
+
+fn error<T>(line: usize, message: String) -> Result<T, AssembleError> {
+    Err(AssembleError { line, message })
+}
+
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.find([';', '#']).map_or(line, |idx| &line[..idx]).trim()
+}
+
+fn resolve(statement: &Statement, pc: usize, labels: &HashMap<&str, usize>, constants: &HashMap<&str, i32>) -> Result<i32, AssembleError> {
+    let operand = statement.operand;
+    if let Ok(value) = operand.parse::<i32>() {
+        return Ok(value);
+    }
+
+    let (negative, name) = match operand.strip_prefix('-') {
+        Some(name) => (true, name),
+        None => (false, operand.strip_prefix('+').unwrap_or(operand)),
+    };
+    if let Some(value) = constants.get(name) {
+        return match (negative, value.checked_neg()) {
+            (false, _) => Ok(*value),
+            (true, Some(negated)) => Ok(negated),
+            (true, None) => error(statement.line, format!("-{} overflows a 32-bit argument", name)),
+        };
+    }
+
+    match labels.get(operand) {
+        Some(_) if statement.mnemonic == "acc" => error(statement.line, format!("acc cannot take label {} as an argument", operand)),
+        Some(target) => Ok(*target as i32 - pc as i32),
+        None => error(statement.line, format!("undefined label or constant: {}", operand)),
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Program, AssembleError> {
+    let mut labels = HashMap::new();
+    let mut constants = HashMap::new();
+    let mut statements = Vec::new();
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line = idx + 1;
+        let mut rest = strip_comment(raw_line);
+
+        if let Some(definition) = rest.strip_prefix("const ") {
+            let (name, value) = match definition.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => return error(line, "expected const NAME = value".to_string()),
+            };
+            if !is_identifier(name) {
+                return error(line, format!("invalid constant name: {}", name));
+            }
+            let value = value.parse::<i32>().or_else(|_| error(line, format!("invalid constant value: {}", value)))?;
+            if constants.insert(name, value).is_some() || labels.contains_key(name) {
+                return error(line, format!("{} is already defined", name));
+            }
+            continue;
+        }
+
+        while let Some((label, remainder)) = rest.split_once(':') {
+            let label = label.trim();
+            if !is_identifier(label) {
+                return error(line, format!("invalid label: {}", label));
+            }
+            if labels.insert(label, statements.len()).is_some() || constants.contains_key(label) {
+                return error(line, format!("{} is already defined", label));
+            }
+            rest = remainder.trim();
+        }
+
+        if rest.is_empty() {
+            continue;
+        }
+        let mut words = rest.split_whitespace();
+        let (mnemonic, operand) = match (words.next(), words.next(), words.next()) {
+            (Some(mnemonic), Some(operand), None) => (mnemonic, operand),
+            _ => return error(line, format!("expected an operation and one argument, found {:?}", rest)),
+        };
+        statements.push(Statement { line, mnemonic, operand });
+    }
+
+    let instructions = statements.iter().enumerate().map(|(pc, statement)| {
+        let operation = Operation::parse(statement.mnemonic).or_else(|_| error(statement.line, format!("unknown operation: {}", statement.mnemonic)))?;
+        let argument = resolve(statement, pc, &labels, &constants)?;
+        Ok(Instruction { operation, argument })
+    }).collect::<Result<_, _>>()?;
+
+    Ok(Program { instructions })
+}
+
+pub fn disassemble(program: &Program) -> String {
+    Analysis::new(program).to_string()
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AssembleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_assemble() {
+        let source = "\
+; the day 8 example, written with labels
+const BIG = 99
+
+start:  nop +0
+top:    acc +1
+        jmp middle      # skip ahead
+back:   acc 3
+        jmp top
+        acc -BIG
+middle: acc +1
+        jmp back
+        acc +6
+";
+        let program = assemble(source).unwrap();
+        assert_eq!(program, Program::parse(SAMPLE).unwrap());
+        assert_eq!(program.to_string(), format!("{}\n", SAMPLE));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let programs = [SAMPLE, "jmp +0\njmp +5\nacc +1\njmp +1", "acc -3\nnop -1\njmp +2\nacc +7"];
+        for text in programs.iter() {
+            let program = Program::parse(text).unwrap();
+            let source = disassemble(&program);
+            assert_eq!(assemble(&source).unwrap(), program, "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source| assemble(source).unwrap_err().to_string();
+
+        assert_eq!(error("jmp nowhere"), "line 1: undefined label or constant: nowhere");
+        assert_eq!(error("a: nop +0\na: jmp a"), "line 2: a is already defined");
+        assert_eq!(error("x: acc x"), "line 1: acc cannot take label x as an argument");
+        assert_eq!(error("nop +0\nmul +2"), "line 2: unknown operation: mul");
+        assert_eq!(error("const N = ten"), "line 1: invalid constant value: ten");
+        assert_eq!(error("const N = -2147483648\nacc -N"), "line 2: -N overflows a 32-bit argument");
+        assert_eq!(error("acc +1 +2"), "line 1: expected an operation and one argument, found \"acc +1 +2\"");
+    }
+}
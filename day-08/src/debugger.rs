@@ -0,0 +1,280 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use crate::{program::Program, vm::{Execution, Handheld, LoopDetector}};
+
+// memory holds (cell, previous value) for just the cells the step changed
+struct Snapshot {
+    pc: usize,
+    registers: Vec<i64>,
+    memory: Vec<(usize, i64)>,
+}
+
+pub struct Debugger<'a> {
+    program: &'a Program,
+    handheld: Handheld<'a>,
+    breakpoints: BTreeSet<usize>,
+    history: Vec<Snapshot>,
+    memory: Vec<i64>,
+    watch: bool,
+    finished: Option<Execution>,
+}
+
+pub const HELP: &str = "\
+commands (line numbers start at 1):
+  step [n]          execute n instructions (default 1)
+  continue          run until a breakpoint, a repeated line or the end
+  back [n]          undo the last n steps (default 1)
+  break <line>      set a breakpoint
+  delete <line>     remove a breakpoint
+  breakpoints       list breakpoints
+  watch             toggle printing accumulator changes
+  list [n]          show the current instruction with n lines of context (default 3)
+  print             show the program counter and accumulator
+  help              show this message
+  quit              exit the debugger
+";
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self::with_handheld(program, Handheld::new(program))
+    }
+
+    pub fn with_handheld(program: &'a Program, handheld: Handheld<'a>) -> Self {
+        Self {
+            program,
+            memory: handheld.state.memory.clone(),
+            handheld,
+            breakpoints: BTreeSet::new(),
+            history: Vec::new(),
+            watch: false,
+            finished: None,
+        }
+    }
+
+    pub fn handheld(&self) -> &Handheld<'a> {
+        &self.handheld
+    }
+
+    pub fn execute(&mut self, command: &str) -> String {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or("");
+        let argument = words.next().map(|word| word.parse::<usize>());
+
+        let mut output = String::new();
+        match (name, argument) {
+            ("step" | "s", None) => self.step(1, &mut output),
+            ("step" | "s", Some(Ok(count))) => self.step(count, &mut output),
+            ("continue" | "c", None) => self.resume(&mut output),
+            ("back" | "rs", None) => self.back(1, &mut output),
+            ("back" | "rs", Some(Ok(count))) => self.back(count, &mut output),
+            ("break" | "b", Some(Ok(line))) if (1..=self.program.len()).contains(&line) => {
+                self.breakpoints.insert(line - 1);
+                let _ = writeln!(output, "breakpoint at line {}", line);
+            },
+            ("delete" | "d", Some(Ok(line))) => {
+                let removed = line > 0 && self.breakpoints.remove(&(line - 1));
+                let _ = writeln!(output, "{}", if removed { "breakpoint removed" } else { "no breakpoint at that line" });
+            },
+            ("breakpoints", None) => {
+                let lines: Vec<String> = self.breakpoints.iter().map(|pc| (pc + 1).to_string()).collect();
+                let _ = writeln!(output, "breakpoints: {}", if lines.is_empty() { "none".to_string() } else { lines.join(", ") });
+            },
+            ("watch" | "w", None) => {
+                self.watch = !self.watch;
+                let _ = writeln!(output, "watching accumulator: {}", if self.watch { "on" } else { "off" });
+            },
+            ("list" | "l", None) => self.list(3, &mut output),
+            ("list" | "l", Some(Ok(context))) => self.list(context, &mut output),
+            ("print" | "p", None) => {
+                let _ = writeln!(output, "line {}, acc = {}", self.handheld.pc + 1, self.handheld.accumulator());
+            },
+            ("help" | "h", None) => output.push_str(HELP),
+            ("", None) => {},
+            _ => {
+                let _ = writeln!(output, "invalid command: {:?} (try 'help')", command.trim());
+            },
+        }
+
+        output
+    }
+
+    fn step_once(&mut self, output: &mut String) -> bool {
+        if let Some(execution) = self.finished {
+            let _ = writeln!(output, "program already finished: {:?}", execution);
+            return false;
+        }
+
+        let (pc, registers) = (self.handheld.pc, self.handheld.state.registers.clone());
+        let accumulator = self.handheld.accumulator();
+
+        if let Some(execution) = self.handheld.step() {
+            self.handheld.pc = pc;
+            self.handheld.state.registers = registers;
+            self.handheld.state.memory.clone_from(&self.memory);
+            self.finished = Some(execution);
+            let _ = writeln!(output, "program finished: {:?}, acc = {}", execution, self.handheld.accumulator());
+            return false;
+        }
+
+        let memory = self.memory.iter_mut().zip(&self.handheld.state.memory).enumerate()
+            .filter_map(|(cell, (previous, current))| if previous != current { Some((cell, std::mem::replace(previous, *current))) } else { None })
+            .collect();
+        self.history.push(Snapshot { pc, registers, memory });
+
+        if self.watch && accumulator != self.handheld.accumulator() {
+            let _ = writeln!(output, "acc: {} -> {} (line {})", accumulator, self.handheld.accumulator(), pc + 1);
+        }
+        true
+    }
+
+    fn step(&mut self, count: usize, output: &mut String) {
+        for _ in 0..count {
+            if !self.step_once(output) {
+                return;
+            }
+        }
+        self.list(0, output);
+    }
+
+    fn resume(&mut self, output: &mut String) {
+        let mut detector = LoopDetector::new(&self.handheld);
+
+        loop {
+            if detector.visit(&self.handheld).is_some() {
+                let _ = writeln!(output, "line {} is about to run again", self.handheld.pc + 1);
+                break;
+            }
+            if !self.step_once(output) {
+                return;
+            }
+            if self.breakpoints.contains(&self.handheld.pc) {
+                let _ = writeln!(output, "hit breakpoint at line {}", self.handheld.pc + 1);
+                break;
+            }
+        }
+        self.list(0, output);
+    }
+
+    fn back(&mut self, count: usize, output: &mut String) {
+        for _ in 0..count {
+            match self.history.pop() {
+                Some(snapshot) => {
+                    self.handheld.pc = snapshot.pc;
+                    self.handheld.state.registers = snapshot.registers;
+                    for (cell, value) in snapshot.memory {
+                        self.handheld.state.memory[cell] = value;
+                        self.memory[cell] = value;
+                    }
+                    self.finished = None;
+                },
+                None => {
+                    let _ = writeln!(output, "at the start of the recorded history");
+                    break;
+                },
+            }
+        }
+        self.list(0, output);
+    }
+
+    fn list(&self, context: usize, output: &mut String) {
+        let pc = self.handheld.pc;
+        let first = pc.saturating_sub(context);
+        let last = (pc + context).min(self.program.len().saturating_sub(1));
+
+        for line in first..=last {
+            let marker = if line == pc { "=>" } else { "  " };
+            let breakpoint = if self.breakpoints.contains(&line) { '*' } else { ' ' };
+            if let Some(instruction) = self.program.instructions.get(line) {
+                let _ = writeln!(output, "{}{}{:>5}  {}", marker, breakpoint, line + 1, instruction.display_with(self.handheld.opcodes()));
+            }
+        }
+        if pc >= self.program.len() {
+            let _ = writeln!(output, "=> {:>5}  <end of program>", pc + 1);
+        }
+        let _ = writeln!(output, "acc = {}", self.handheld.accumulator());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_step_and_back() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(debugger.execute("step 2"), "=>     3  jmp +4\nacc = 1\n");
+        assert_eq!(debugger.handheld().pc, 2);
+
+        debugger.execute("back");
+        assert_eq!(debugger.handheld().pc, 1);
+        assert_eq!(debugger.handheld().accumulator(), 0);
+        assert!(debugger.execute("back 5").contains("at the start of the recorded history"));
+    }
+
+    #[test]
+    fn test_back_restores_memory() {
+        let mut opcodes = crate::program::OpcodeTable::default();
+        opcodes.register("st", |state, argument| {
+            state.memory[argument as usize] = state.registers[0];
+            1
+        }).unwrap();
+
+        let program = Program::parse_with("acc +4\nst +1\nacc +1\nst +1", &opcodes).unwrap();
+        let handheld = Handheld::with_config(&program, crate::vm::Config { registers: 1, memory: 2 }, opcodes);
+        let mut debugger = Debugger::with_handheld(&program, handheld);
+
+        debugger.execute("step 4");
+        assert_eq!(debugger.handheld().state.memory, vec![0, 5]);
+        debugger.execute("back");
+        assert_eq!((debugger.handheld().pc, debugger.handheld().state.memory.clone()), (3, vec![0, 4]));
+        debugger.execute("back 2");
+        assert_eq!((debugger.handheld().pc, debugger.handheld().state.memory.clone()), (1, vec![0, 0]));
+        assert!(debugger.execute("continue").contains("program finished: Halted, acc = 5"));
+        assert_eq!(debugger.handheld().state.memory, vec![0, 5]);
+    }
+
+    #[test]
+    fn test_breakpoints_and_loops() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        assert_eq!(debugger.execute("break 5"), "breakpoint at line 5\n");
+        assert!(debugger.execute("continue").contains("hit breakpoint at line 5"));
+        assert_eq!(debugger.handheld().accumulator(), 5);
+        assert_eq!(debugger.execute("breakpoints"), "breakpoints: 5\n");
+
+        assert_eq!(debugger.execute("delete 5"), "breakpoint removed\n");
+        assert!(debugger.execute("continue").contains("line 5 is about to run again"));
+        assert_eq!(debugger.handheld().accumulator(), 10);
+    }
+
+    #[test]
+    fn test_watch_and_finish() {
+        let program = Program::parse("acc +2\nnop +0\nacc -1").unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        debugger.execute("watch");
+        let output = debugger.execute("continue");
+        assert!(output.contains("acc: 0 -> 2 (line 1)"));
+        assert!(output.contains("acc: 2 -> 1 (line 3)"));
+        assert!(output.contains("program finished: Halted, acc = 1"));
+        assert!(debugger.execute("step").contains("program already finished"));
+        assert!(debugger.execute("frobnicate").starts_with("invalid command"));
+    }
+
+    #[test]
+    fn test_stateful_custom_opcodes() {
+        let opcodes = crate::program::counting_opcodes();
+
+        let program = Program::parse_with("set +3\nacc +1\ndjnz -1", &opcodes).unwrap();
+        let handheld = Handheld::with_config(&program, crate::vm::Config { registers: 2, memory: 0 }, opcodes);
+        let mut debugger = Debugger::with_handheld(&program, handheld);
+
+        assert!(debugger.execute("continue").contains("program finished: Halted, acc = 3"));
+        assert!(debugger.execute("list").contains("    3  djnz -1\n"));
+    }
+}
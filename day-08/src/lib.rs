@@ -0,0 +1,15 @@
+pub mod analysis;
+pub mod assembler;
+pub mod debugger;
+pub mod program;
+pub mod repair;
+pub mod trace;
+pub mod vm;
+
+pub use analysis::{Analysis, BasicBlock, Issue};
+pub use assembler::{assemble, disassemble, AssembleError};
+pub use debugger::Debugger;
+pub use program::{Instruction, Opcode, OpcodeTable, Operation, Program, WithOpcodes};
+pub use repair::Repair;
+pub use trace::{Coverage, Trace, TraceEntry};
+pub use vm::{Config, Execution, Handheld, LoopDetector, State};
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+use crate::vm::State;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Instruction {
+    pub operation: Operation,
+    pub argument: i32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Acc,
+    Jmp,
+    Nop,
+    Custom(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+}
+
+pub type Handler = fn(&mut State, i32) -> isize;
+
+#[derive(Clone)]
+pub struct Opcode {
+    pub mnemonic: String,
+    pub execute: Handler,
+}
+
+pub struct WithOpcodes<'a, T> {
+    item: &'a T,
+    opcodes: &'a OpcodeTable,
+}
+
+#[derive(Clone, Default)]
+pub struct OpcodeTable {
+    custom: Vec<Opcode>,
+    by_mnemonic: HashMap<String, usize>,
+}
+
+impl OpcodeTable {
+    pub fn register(&mut self, mnemonic: &str, execute: Handler) -> Result<Operation> {
+        if Operation::parse_builtin(mnemonic).is_some() || self.by_mnemonic.contains_key(mnemonic) {
+            return Err(format!("opcode {} is already defined", mnemonic).into());
+        }
+
+        let id = self.custom.len();
+        self.custom.push(Opcode { mnemonic: mnemonic.to_string(), execute });
+        self.by_mnemonic.insert(mnemonic.to_string(), id);
+        Ok(Operation::Custom(id))
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Opcode> {
+        self.custom.get(id)
+    }
+
+    pub fn lookup(&self, mnemonic: &str) -> Option<Operation> {
+        Operation::parse_builtin(mnemonic).or_else(|| self.by_mnemonic.get(mnemonic).map(|id| Operation::Custom(*id)))
+    }
+
+    pub fn mnemonic(&self, operation: Operation) -> &str {
+        match operation {
+            Operation::Acc => "acc",
+            Operation::Jmp => "jmp",
+            Operation::Nop => "nop",
+            Operation::Custom(id) => self.custom.get(id).map_or("???", |opcode| opcode.mnemonic.as_str()),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, &OpcodeTable::default())
+    }
+
+    pub fn parse_with(input: &str, opcodes: &OpcodeTable) -> Result<Self> {
+        let mut parts = input.split_whitespace();
+        let mnemonic = parts.next().ok_or("missing operation")?;
+        let operation = opcodes.lookup(mnemonic).ok_or_else(|| format!("unknown operation: {}", mnemonic))?;
+        let argument = parts.next().ok_or("missing argument")?.parse::<i32>()?;
+
+        Ok(Self { operation, argument })
+    }
+
+    pub fn display_with<'a>(&'a self, opcodes: &'a OpcodeTable) -> WithOpcodes<'a, Self> {
+        WithOpcodes { item: self, opcodes }
+    }
+}
+
+impl Operation {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_builtin(input).ok_or_else(|| "unknown operation".into())
+    }
+
+    fn parse_builtin(input: &str) -> Option<Self> {
+        match input {
+            "acc" => Some(Operation::Acc),
+            "jmp" => Some(Operation::Jmp),
+            "nop" => Some(Operation::Nop),
+            _ => None,
+        }
+    }
+}
+
+impl Program {
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, &OpcodeTable::default())
+    }
+
+    pub fn parse_with(input: &str, opcodes: &OpcodeTable) -> Result<Self> {
+        let instructions = input.lines().map(|line| Instruction::parse_with(line, opcodes)).collect::<Result<_>>()?;
+        Ok(Self { instructions })
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn display_with<'a>(&'a self, opcodes: &'a OpcodeTable) -> WithOpcodes<'a, Self> {
+        WithOpcodes { item: self, opcodes }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operation {
+            Operation::Custom(id) => write!(f, "op#{} {:+}", id, self.argument),
+            operation => write!(f, "{} {:+}", OpcodeTable::default().mnemonic(operation), self.argument),
+        }
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for instruction in self.instructions.iter() {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WithOpcodes<'_, Instruction> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:+}", self.opcodes.mnemonic(self.item.operation), self.item.argument)
+    }
+}
+
+impl fmt::Display for WithOpcodes<'_, Program> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for instruction in self.item.instructions.iter() {
+            writeln!(f, "{}", instruction.display_with(self.opcodes))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn counting_opcodes() -> OpcodeTable {
+    let mut opcodes = OpcodeTable::default();
+    opcodes.register("set", |state, argument| {
+        state.registers[1] = argument as i64;
+        1
+    }).unwrap();
+    opcodes.register("djnz", |state, argument| {
+        state.registers[1] -= 1;
+        if state.registers[1] != 0 { argument as isize } else { 1 }
+    }).unwrap();
+    opcodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_with_opcodes() {
+        let mut opcodes = OpcodeTable::default();
+        let mul = opcodes.register("mul", |state, argument| {
+            state.registers[0] *= argument as i64;
+            1
+        }).unwrap();
+
+        let program = Program::parse_with("acc +3\nmul -7\njmp +1", &opcodes).unwrap();
+        let text = program.display_with(&opcodes).to_string();
+
+        assert_eq!(text, "acc +3\nmul -7\njmp +1\n");
+        assert_eq!(Program::parse_with(&text, &opcodes).unwrap(), program);
+        assert_eq!(Instruction { operation: mul, argument: 2 }.to_string(), "op#0 +2");
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::VecDeque, error::Error, fmt};
+
+use crate::{program::{Instruction, Operation, Program}, vm::{Execution, Handheld}};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Repair {
+    pub pc: usize,
+    pub original: Instruction,
+    pub replacement: Instruction,
+    pub accumulator: i64,
+}
+
+fn target(pc: usize, instruction: Instruction) -> isize {
+    match instruction.operation {
+        Operation::Jmp => pc as isize + instruction.argument as isize,
+        _ => pc as isize + 1,
+    }
+}
+
+fn flipped(instruction: Instruction) -> Option<Instruction> {
+    let operation = match instruction.operation {
+        Operation::Jmp => Operation::Nop,
+        Operation::Nop => Operation::Jmp,
+        _ => return None,
+    };
+    Some(Instruction { operation, ..instruction })
+}
+
+fn terminating(program: &Program) -> Vec<bool> {
+    let end = program.len();
+    let mut predecessors = vec![Vec::new(); end + 1];
+    for (pc, instruction) in program.instructions.iter().enumerate() {
+        let target = target(pc, *instruction);
+        if (0..=end as isize).contains(&target) {
+            predecessors[target as usize].push(pc);
+        }
+    }
+
+    let mut reaches_end = vec![false; end + 1];
+    let mut queue = VecDeque::from(vec![end]);
+    reaches_end[end] = true;
+    while let Some(pc) = queue.pop_front() {
+        for &predecessor in predecessors[pc].iter() {
+            if !reaches_end[predecessor] {
+                reaches_end[predecessor] = true;
+                queue.push_back(predecessor);
+            }
+        }
+    }
+
+    reaches_end
+}
+
+pub fn repair(program: &Program) -> Result<Repair> {
+    if program.instructions.iter().any(|instruction| matches!(instruction.operation, Operation::Custom(_))) {
+        return Err("cannot analyse programs with custom opcodes".into());
+    }
+
+    let reaches_end = terminating(program);
+    if reaches_end[0] {
+        return Err("program already terminates".into());
+    }
+
+    let mut visited = vec![false; program.len()];
+    let mut pc = 0;
+    let fix = loop {
+        match visited.get_mut(pc) {
+            Some(seen) if !*seen => *seen = true,
+            _ => return Err("no single jmp/nop flip makes the program terminate".into()),
+        }
+
+        let original = program.instructions[pc];
+        if let Some(replacement) = flipped(original) {
+            let target = target(pc, replacement);
+            if (0..reaches_end.len() as isize).contains(&target) && reaches_end[target as usize] {
+                break (pc, original, replacement);
+            }
+        }
+
+        match target(pc, original) {
+            next if next >= 0 => pc = next as usize,
+            _ => return Err("no single jmp/nop flip makes the program terminate".into()),
+        }
+    };
+
+    let (pc, original, replacement) = fix;
+    let mut repaired = program.clone();
+    repaired.instructions[pc] = replacement;
+
+    let mut handheld = Handheld::new(&repaired);
+    let execution = handheld.run();
+    if execution != Execution::Halted {
+        return Err(format!("repaired program did not halt: {:?}", execution).into());
+    }
+
+    Ok(Repair { pc, original, replacement, accumulator: handheld.accumulator() })
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} -> {}", self.pc + 1, self.original, self.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_repair() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let repair = repair(&program).unwrap();
+
+        assert_eq!(repair.pc, 7);
+        assert_eq!(repair.accumulator, 8);
+        assert_eq!(repair.to_string(), "line 8: jmp -4 -> nop -4");
+    }
+
+    #[test]
+    fn test_unrepairable() {
+        assert!(repair(&Program::parse("acc +1\nacc +2").unwrap()).is_err());
+        assert!(repair(&Program::parse("jmp +0\nacc +1").unwrap()).is_ok());
+        assert!(repair(&Program::parse("acc +1\njmp -1\njmp -2").unwrap()).is_err());
+    }
+}
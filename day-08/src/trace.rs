@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::{program::Instruction, vm::{Execution, Handheld, LoopDetector}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceEntry {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub before: i64,
+    pub after: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+    pub execution: Execution,
+    cycle_start: Option<usize>,
+    hits: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage<'a> {
+    pub hits: &'a [usize],
+}
+
+impl Trace {
+    pub fn record(handheld: &mut Handheld) -> Self {
+        let mut entries = Vec::new();
+        let mut hits = vec![0; handheld.instructions().len()];
+        let mut detector = LoopDetector::new(handheld);
+        let mut cycle_start = None;
+
+        let execution = loop {
+            let pc = handheld.pc;
+            if let Some(first) = detector.visit(handheld) {
+                cycle_start = Some(first);
+                break Execution::Looped { pc };
+            }
+
+            let instruction = handheld.current_instruction().copied();
+            let before = handheld.accumulator();
+            if let Some(execution) = handheld.step() {
+                break execution;
+            }
+
+            if let Some(instruction) = instruction {
+                hits[pc] += 1;
+                entries.push(TraceEntry { pc, instruction, before, after: handheld.accumulator() });
+            }
+        };
+
+        Self { entries, execution, cycle_start, hits }
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.entries.last().map_or(0, |entry| entry.after)
+    }
+
+    pub fn cycle(&self) -> Option<&[TraceEntry]> {
+        self.cycle_start.map(|start| &self.entries[start..])
+    }
+
+    pub fn coverage(&self) -> Coverage<'_> {
+        Coverage { hits: &self.hits }
+    }
+}
+
+impl Coverage<'_> {
+    pub fn never_executed(&self) -> Vec<usize> {
+        self.hits.iter().enumerate().filter(|(_, count)| **count == 0).map(|(pc, _)| pc).collect()
+    }
+
+    pub fn executed(&self) -> usize {
+        self.hits.len() - self.never_executed().len()
+    }
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>5}  {:<10} acc {} -> {}", self.pc + 1, self.instruction.to_string(), self.before, self.after)
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in self.entries.iter() {
+            writeln!(f, "{}", entry)?;
+        }
+        writeln!(f, "{:?}, acc = {}", self.execution, self.accumulator())?;
+
+        if let Some(cycle) = self.cycle() {
+            let lines: Vec<String> = cycle.iter().map(|entry| (entry.pc + 1).to_string()).collect();
+            writeln!(f, "loop of {} instructions: {} -> {}", cycle.len(), lines.join(" -> "), lines[0])?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Coverage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.hits.len();
+        let executed = self.executed();
+        let percentage = if total == 0 { 100.0 } else { executed as f64 * 100.0 / total as f64 };
+        writeln!(f, "executed {} of {} lines ({:.1}%)", executed, total, percentage)?;
+
+        for (pc, count) in self.hits.iter().enumerate() {
+            writeln!(f, "{:>5}  {}", pc + 1, count)?;
+        }
+
+        let never: Vec<String> = self.never_executed().iter().map(|pc| (pc + 1).to_string()).collect();
+        writeln!(f, "never executed: {}", if never.is_empty() { "none".to_string() } else { never.join(", ") })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::Program;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_trace() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let trace = Trace::record(&mut Handheld::new(&program));
+
+        assert_eq!(trace.execution, Execution::Looped { pc: 1 });
+        assert_eq!(trace.accumulator(), 5);
+        assert_eq!(trace.entries.len(), 7);
+        assert_eq!(trace.entries[3], TraceEntry { pc: 6, instruction: Instruction::parse("acc +1").unwrap(), before: 1, after: 2 });
+
+        let cycle: Vec<usize> = trace.cycle().unwrap().iter().map(|entry| entry.pc).collect();
+        assert_eq!(cycle, vec![1, 2, 6, 7, 3, 4]);
+        assert!(trace.to_string().ends_with("loop of 6 instructions: 2 -> 3 -> 7 -> 8 -> 4 -> 5 -> 2\n"));
+    }
+
+    #[test]
+    fn test_coverage() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let trace = Trace::record(&mut Handheld::new(&program));
+        let coverage = trace.coverage();
+
+        assert_eq!(coverage.never_executed(), vec![5, 8]);
+        assert_eq!(coverage.executed(), 7);
+        assert!(coverage.to_string().starts_with("executed 7 of 9 lines (77.8%)\n"));
+
+        let program = Program::parse("acc +2\njmp +2\nacc +1").unwrap();
+        let trace = Trace::record(&mut Handheld::new(&program));
+        assert_eq!(trace.execution, Execution::Halted);
+        assert_eq!(trace.cycle(), None);
+        assert_eq!(trace.coverage().never_executed(), vec![2]);
+    }
+
+    #[test]
+    fn test_repeated_lines_with_custom_opcodes() {
+        let opcodes = crate::program::counting_opcodes();
+
+        let program = Program::parse_with("set +3\nacc +1\ndjnz -1\nnop +0", &opcodes).unwrap();
+        let mut handheld = Handheld::with_config(&program, crate::vm::Config { registers: 2, memory: 0 }, opcodes);
+        let trace = Trace::record(&mut handheld);
+
+        assert_eq!(trace.execution, Execution::Halted);
+        assert_eq!(trace.accumulator(), 3);
+        assert_eq!(trace.coverage().hits, &[1, 3, 3, 1]);
+        assert_eq!(trace.cycle(), None);
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::{hash_map::DefaultHasher, HashMap}, hash::{Hash, Hasher}};
+
+use crate::program::{Instruction, OpcodeTable, Operation, Program};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    pub registers: usize,
+    pub memory: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct State {
+    pub registers: Vec<i64>,
+    pub memory: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Execution {
+    Halted,
+    Looped { pc: usize },
+    OutOfBounds { pc: isize },
+    InvalidOpcode { pc: usize },
+}
+
+// Built-in opcodes never branch on state, so a repeated line proves a loop. Custom
+// handlers can, so those programs only loop once a (pc, state) pair repeats. Only a
+// hash of each state is kept; on a match the earlier state is rebuilt by replaying
+// from the starting point and compared in full.
+#[derive(Clone)]
+pub struct LoopDetector<'a> {
+    start: Option<Handheld<'a>>,
+    seen: HashMap<(usize, u64), Vec<usize>>,
+    steps: usize,
+}
+
+#[derive(Clone)]
+pub struct Handheld<'a> {
+    pub pc: usize,
+    pub state: State,
+    instructions: &'a [Instruction],
+    opcodes: OpcodeTable,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { registers: 1, memory: 0 }
+    }
+}
+
+impl State {
+    pub fn new(config: Config) -> Self {
+        Self { registers: vec![0; config.registers.max(1)], memory: vec![0; config.memory] }
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.registers[0]
+    }
+}
+
+impl<'a> LoopDetector<'a> {
+    pub fn new(handheld: &Handheld<'a>) -> Self {
+        let by_state = handheld.instructions.iter().any(|instruction| matches!(instruction.operation, Operation::Custom(_)));
+        Self { start: if by_state { Some(handheld.clone()) } else { None }, seen: HashMap::new(), steps: 0 }
+    }
+
+    pub fn visit(&mut self, handheld: &Handheld) -> Option<usize> {
+        let step = self.steps;
+        self.steps += 1;
+
+        let hash = match self.start {
+            Some(_) => {
+                let mut hasher = DefaultHasher::new();
+                handheld.state.hash(&mut hasher);
+                hasher.finish()
+            },
+            None => 0,
+        };
+
+        let start = &self.start;
+        let earlier_steps = self.seen.entry((handheld.pc, hash)).or_default();
+        let first = earlier_steps.iter().copied().find(|earlier| match start {
+            Some(start) => start.replay(*earlier).state == handheld.state,
+            None => true,
+        });
+        if first.is_none() {
+            earlier_steps.push(step);
+        }
+        first
+    }
+}
+
+impl<'a> Handheld<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self::with_config(program, Config::default(), OpcodeTable::default())
+    }
+
+    pub fn with_config(program: &'a Program, config: Config, opcodes: OpcodeTable) -> Self {
+        Self { pc: 0, state: State::new(config), instructions: &program.instructions, opcodes }
+    }
+
+    pub fn accumulator(&self) -> i64 {
+        self.state.accumulator()
+    }
+
+    pub fn instructions(&self) -> &'a [Instruction] {
+        self.instructions
+    }
+
+    pub fn opcodes(&self) -> &OpcodeTable {
+        &self.opcodes
+    }
+
+    pub fn current_instruction(&self) -> Option<&Instruction> {
+        self.instructions.get(self.pc)
+    }
+
+    pub fn step(&mut self) -> Option<Execution> {
+        let Instruction { operation, argument } = match self.instructions.get(self.pc) {
+            Some(instruction) => *instruction,
+            None if self.pc == self.instructions.len() => return Some(Execution::Halted),
+            None => return Some(Execution::OutOfBounds { pc: self.pc as isize }),
+        };
+
+        let offset = match operation {
+            Operation::Acc => {
+                self.state.registers[0] += argument as i64;
+                1
+            },
+            Operation::Jmp => argument as isize,
+            Operation::Nop => 1,
+            Operation::Custom(id) => match self.opcodes.get(id) {
+                Some(opcode) => (opcode.execute)(&mut self.state, argument),
+                None => return Some(Execution::InvalidOpcode { pc: self.pc }),
+            },
+        };
+
+        let target = self.pc as isize + offset;
+        if target < 0 || target as usize > self.instructions.len() {
+            return Some(Execution::OutOfBounds { pc: target });
+        }
+        self.pc = target as usize;
+
+        None
+    }
+
+    fn replay(&self, steps: usize) -> Self {
+        let mut handheld = self.clone();
+        for _ in 0..steps {
+            handheld.step();
+        }
+        handheld
+    }
+
+    pub fn run(&mut self) -> Execution {
+        let mut detector = LoopDetector::new(self);
+
+        loop {
+            if detector.visit(self).is_some() {
+                return Execution::Looped { pc: self.pc };
+            }
+
+            if let Some(execution) = self.step() {
+                return execution;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_looped() {
+        let program = Program::parse(SAMPLE).unwrap();
+        let mut handheld = Handheld::new(&program);
+
+        assert_eq!(handheld.run(), Execution::Looped { pc: 1 });
+        assert_eq!(handheld.accumulator(), 5);
+    }
+
+    #[test]
+    fn test_halted_and_out_of_bounds() {
+        let program = Program::parse("acc +1\njmp +2\nacc +5").unwrap();
+        let mut handheld = Handheld::new(&program);
+        assert_eq!(handheld.run(), Execution::Halted);
+        assert_eq!(handheld.accumulator(), 1);
+
+        let program = Program::parse("acc +1\njmp -2").unwrap();
+        assert_eq!(Handheld::new(&program).run(), Execution::OutOfBounds { pc: -1 });
+
+        let program = Program::parse("jmp +3\nnop +0").unwrap();
+        assert_eq!(Handheld::new(&program).run(), Execution::OutOfBounds { pc: 3 });
+    }
+
+    #[test]
+    fn test_custom_opcodes() {
+        let mut opcodes = OpcodeTable::default();
+        opcodes.register("mul", |state, argument| {
+            state.registers[0] *= argument as i64;
+            1
+        }).unwrap();
+        opcodes.register("st", |state, argument| {
+            state.memory[argument as usize] = state.registers[0];
+            1
+        }).unwrap();
+        assert!(opcodes.register("acc", |_, _| 1).is_err());
+
+        let program = Program::parse_with("acc +3\nmul +7\nst +1", &opcodes).unwrap();
+        let mut handheld = Handheld::with_config(&program, Config { registers: 2, memory: 4 }, opcodes);
+
+        assert_eq!(handheld.run(), Execution::Halted);
+        assert_eq!(handheld.accumulator(), 21);
+        assert_eq!(handheld.state.memory, vec![0, 21, 0, 0]);
+        assert!(Program::parse("mul +7").is_err());
+    }
+
+    #[test]
+    fn test_stateful_custom_opcodes() {
+        let opcodes = crate::program::counting_opcodes();
+
+        let program = Program::parse_with("set +3\nacc +1\ndjnz -1", &opcodes).unwrap();
+        let mut handheld = Handheld::with_config(&program, Config { registers: 2, memory: 0 }, opcodes.clone());
+        assert_eq!(handheld.run(), Execution::Halted);
+        assert_eq!(handheld.accumulator(), 3);
+
+        let program = Program::parse_with("set +2\nnop +0\njmp -1", &opcodes).unwrap();
+        let mut handheld = Handheld::with_config(&program, Config { registers: 2, memory: 0 }, opcodes);
+        assert_eq!(handheld.run(), Execution::Looped { pc: 1 });
+    }
+}
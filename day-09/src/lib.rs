@@ -0,0 +1,2 @@
+pub mod weakness;
+pub mod xmas;
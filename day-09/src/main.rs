@@ -1,5 +1,5 @@
-use std::{error::Error, fs::read_to_string};
-use itertools::Itertools;
+use std::{env, error::Error, fs::read_to_string};
+use day_09::{weakness::{find_weakness, Weakness}, xmas::{invalid_entries, validate, Mode, Verdict}};
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
@@ -7,42 +7,38 @@ fn main() -> Result<()> {
     let input = read_to_string("input/input1.txt")?;
 
     let numbers = input.lines()
-        .map(|line| line.parse::<usize>().map_err(|_| "invalid number".into()))
-        .collect::<Result<Vec<usize>>>()?;
-    
-    let invalid_entry = find_invalid_entry(numbers.clone(), 25);
-    println!("Part 1: {}", invalid_entry.ok_or("no invalid entry found")?);
-
-    let weakness = find_encryption_weakness(numbers, 25)?;
-    println!("Part 2: {}", weakness);
+        .map(|line| line.parse::<u64>().map_err(|_| "invalid number".into()))
+        .collect::<Result<Vec<u64>>>()?;
+
+    let invalid_entry = find_invalid_entry(numbers.iter().copied(), 25).ok_or("no invalid entry found")?;
+    println!("Part 1: {}", invalid_entry);
+
+    let weakness = find_encryption_weakness(&numbers, invalid_entry)?;
+    println!("Part 2: {} (lines {}-{})", weakness.value(), weakness.range.start + 1, weakness.range.end);
+
+    let audit = match env::args().nth(1).as_deref() {
+        Some("--audit") => Some(Mode::Strict),
+        Some("--audit=healing") => Some(Mode::SelfHealing),
+        Some(_) => return Err("usage: day-09 [--audit | --audit=healing]".into()),
+        None => None,
+    };
+    if let Some(mode) = audit {
+        for entry in invalid_entries(numbers, 25, mode) {
+            println!("{}", entry);
+        }
+    }
 
     Ok(())
 }
 
-fn find_invalid_entry(sequence: Vec<usize>, preamble_length: usize) -> Option<usize> {
-    sequence
-        .windows(preamble_length)
-        .zip(sequence.iter().skip(preamble_length))
-        .find_map(|(previous, candidate)| {
-            if previous.iter().tuple_combinations().find(|(x, y)| *x + *y == *candidate).is_none() {
-                Some(*candidate)
-            } else {
-                None
-            }
-        })
+fn find_invalid_entry<I: IntoIterator<Item = u64>>(numbers: I, preamble_length: usize) -> Option<u64> {
+    validate(numbers, preamble_length)
+        .find(|(_, _, verdict)| *verdict == Verdict::Invalid)
+        .map(|(_, number, _)| number)
 }
 
-fn find_encryption_weakness(sequence: Vec<usize>, preamble_length: usize) -> Result<usize> {
-    let invalid_entry = find_invalid_entry(sequence.clone(), preamble_length).ok_or("no invalid entry found")?;
-
-    (2..sequence.len()).find_map(|window_size| {
-        sequence.windows(window_size).find_map(|contiguous_block| {
-            if contiguous_block.iter().sum::<usize>() != invalid_entry {
-                return None;
-            }
-            Some(contiguous_block.iter().min().unwrap() + contiguous_block.iter().max().unwrap())
-        })
-    }).ok_or("encryption has no weakness".into())
+fn find_encryption_weakness(sequence: &[u64], invalid_entry: u64) -> Result<Weakness> {
+    find_weakness(sequence, invalid_entry).ok_or_else(|| "encryption has no weakness".into())
 }
 
 #[cfg(test)]
@@ -51,16 +47,17 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let input: Vec<usize> = vec![35,20,15,25,47,40,62,55,65,95,102,117,150,182,127,219,299,277,309,576];
+        let input: Vec<u64> = vec![35,20,15,25,47,40,62,55,65,95,102,117,150,182,127,219,299,277,309,576];
         let result = find_invalid_entry(input, 5);
         assert_eq!(result, Some(127));
     }
 
     #[test]
     fn test_part_2() -> Result<()> {
-        let input: Vec<usize> = vec![35,20,15,25,47,40,62,55,65,95,102,117,150,182,127,219,299,277,309,576];
-        let result = find_encryption_weakness(input, 5)?;
-        assert_eq!(result, 62);
+        let input: Vec<u64> = vec![35,20,15,25,47,40,62,55,65,95,102,117,150,182,127,219,299,277,309,576];
+        let invalid_entry = find_invalid_entry(input.iter().copied(), 5).ok_or("no invalid entry found")?;
+        let result = find_encryption_weakness(&input, invalid_entry)?;
+        assert_eq!(result.value(), 62);
         Ok(())
     }
 }
@@ -0,0 +1,102 @@
+use std::{collections::HashMap, ops::Range};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weakness {
+    pub range: Range<usize>,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Weakness {
+    fn from_range(numbers: &[u64], range: Range<usize>) -> Self {
+        let block = &numbers[range.clone()];
+        let min = *block.iter().min().unwrap_or(&0);
+        let max = *block.iter().max().unwrap_or(&0);
+        Self { range, min, max }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.min + self.max
+    }
+}
+
+pub fn find_range(numbers: &[u64], target: u64) -> Option<Range<usize>> {
+    let target = target as u128;
+    let mut start = 0;
+    let mut sum = 0u128;
+
+    for (end, number) in numbers.iter().enumerate() {
+        sum += *number as u128;
+        while sum > target {
+            sum -= numbers[start] as u128;
+            start += 1;
+        }
+        if sum == target && end > start {
+            return Some(start..end + 1);
+        }
+    }
+
+    None
+}
+
+pub fn ranges_summing_to(numbers: &[u64], target: u64) -> Vec<Range<usize>> {
+    let target = target as u128;
+    let mut starts: HashMap<u128, Vec<usize>> = HashMap::new();
+    let mut ranges = Vec::new();
+    let mut prefix = 0u128;
+
+    for (end, number) in numbers.iter().enumerate() {
+        starts.entry(prefix).or_default().push(end);
+        prefix += *number as u128;
+
+        if let Some(matching) = prefix.checked_sub(target).and_then(|previous| starts.get(&previous)) {
+            ranges.extend(matching.iter().filter(|start| **start < end).map(|start| *start..end + 1));
+        }
+    }
+
+    ranges
+}
+
+pub fn find_weakness(numbers: &[u64], target: u64) -> Option<Weakness> {
+    find_range(numbers, target).map(|range| Weakness::from_range(numbers, range))
+}
+
+pub fn all_weaknesses(numbers: &[u64], target: u64) -> Vec<Weakness> {
+    ranges_summing_to(numbers, target).into_iter().map(|range| Weakness::from_range(numbers, range)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: [u64; 20] = [35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576];
+
+    #[test]
+    fn test_find_weakness() {
+        let weakness = find_weakness(&SAMPLE, 127).unwrap();
+
+        assert_eq!(weakness, Weakness { range: 2..6, min: 15, max: 47 });
+        assert_eq!(weakness.value(), 62);
+        assert_eq!(find_range(&SAMPLE, 47), None);
+        assert_eq!(find_range(&SAMPLE, 1), None);
+    }
+
+    #[test]
+    fn test_all_ranges() {
+        assert_eq!(ranges_summing_to(&SAMPLE, 127), vec![2..6]);
+        assert_eq!(ranges_summing_to(&[1, 2, 0, 3, 3], 3), vec![0..2, 0..3, 2..4]);
+        assert_eq!(ranges_summing_to(&[4, 0, 0], 4), vec![0..2, 0..3]);
+        assert_eq!(find_range(&[4, 0, 0], 4), Some(0..2));
+    }
+
+    #[test]
+    fn test_near_overflow() {
+        let numbers = [u64::MAX, u64::MAX - 1, 1, 3, u64::MAX - 3];
+
+        assert_eq!(find_range(&numbers, u64::MAX), Some(1..3));
+        assert_eq!(ranges_summing_to(&numbers, u64::MAX), vec![1..3, 3..5]);
+        assert_eq!(find_weakness(&numbers, u64::MAX).unwrap().value(), u64::MAX);
+        assert_eq!(all_weaknesses(&numbers, u64::MAX)[1], Weakness { range: 3..5, min: 3, max: u64::MAX - 3 });
+        assert_eq!(ranges_summing_to(&numbers, 4), vec![2..4]);
+    }
+}
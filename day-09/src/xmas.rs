@@ -0,0 +1,176 @@
+use std::{collections::{HashMap, VecDeque}, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Preamble,
+    Valid,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Strict,
+    SelfHealing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEntry {
+    pub index: usize,
+    pub number: u64,
+    pub window: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct XmasValidator {
+    preamble_length: usize,
+    window: VecDeque<u64>,
+    sums: HashMap<u64, usize>,
+}
+
+impl XmasValidator {
+    pub fn new(preamble_length: usize) -> Self {
+        Self { preamble_length, window: VecDeque::with_capacity(preamble_length + 1), sums: HashMap::new() }
+    }
+
+    pub fn window(&self) -> impl Iterator<Item = &u64> {
+        self.window.iter()
+    }
+
+    pub fn check(&self, number: u64) -> Verdict {
+        if self.window.len() < self.preamble_length {
+            Verdict::Preamble
+        } else if self.sums.contains_key(&number) {
+            Verdict::Valid
+        } else {
+            Verdict::Invalid
+        }
+    }
+
+    pub fn admit(&mut self, number: u64) {
+        if self.window.len() == self.preamble_length {
+            if let Some(oldest) = self.window.pop_front() {
+                for other in self.window.iter().filter(|other| **other != oldest) {
+                    if let Some(sum) = oldest.checked_add(*other) {
+                        if let Some(count) = self.sums.get_mut(&sum) {
+                            *count -= 1;
+                            if *count == 0 {
+                                self.sums.remove(&sum);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if self.preamble_length == 0 {
+            return;
+        }
+
+        for other in self.window.iter().filter(|other| **other != number) {
+            if let Some(sum) = number.checked_add(*other) {
+                *self.sums.entry(sum).or_insert(0) += 1;
+            }
+        }
+        self.window.push_back(number);
+    }
+
+    pub fn push(&mut self, number: u64) -> Verdict {
+        let verdict = self.check(number);
+        self.admit(number);
+        verdict
+    }
+}
+
+pub fn validate<I: IntoIterator<Item = u64>>(numbers: I, preamble_length: usize) -> impl Iterator<Item = (usize, u64, Verdict)> {
+    let mut validator = XmasValidator::new(preamble_length);
+    numbers.into_iter().enumerate().map(move |(idx, number)| (idx, number, validator.push(number)))
+}
+
+pub fn invalid_entries<I: IntoIterator<Item = u64>>(numbers: I, preamble_length: usize, mode: Mode) -> impl Iterator<Item = InvalidEntry> {
+    let mut validator = XmasValidator::new(preamble_length);
+
+    numbers.into_iter().enumerate().filter_map(move |(index, number)| {
+        if validator.check(number) != Verdict::Invalid {
+            validator.admit(number);
+            return None;
+        }
+
+        let entry = InvalidEntry { index, number, window: validator.window().copied().collect() };
+        if mode == Mode::Strict {
+            validator.admit(number);
+        }
+        Some(entry)
+    })
+}
+
+impl fmt::Display for InvalidEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let window: Vec<String> = self.window.iter().map(u64::to_string).collect();
+        write!(f, "#{}: {} is not the sum of two of [{}]", self.index + 1, self.number, window.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator() {
+        let mut validator = XmasValidator::new(25);
+        for number in 1..=25 {
+            assert_eq!(validator.push(number), Verdict::Preamble);
+        }
+
+        assert_eq!(validator.check(26), Verdict::Valid);
+        assert_eq!(validator.check(49), Verdict::Valid);
+        assert_eq!(validator.check(100), Verdict::Invalid);
+        assert_eq!(validator.check(50), Verdict::Invalid);
+
+        validator.push(45);
+        assert_eq!(validator.check(3), Verdict::Invalid);
+        assert_eq!(validator.check(26), Verdict::Valid);
+        assert_eq!(validator.check(65), Verdict::Valid);
+        assert_eq!(validator.check(71), Verdict::Invalid);
+    }
+
+    #[test]
+    fn test_validate_stream() {
+        let numbers = "35 20 15 25 47 40 62 55 65 95 102 117 150 182 127 219".split(' ').map(|word| word.parse().unwrap());
+        let invalid: Vec<(usize, u64)> = validate(numbers, 5)
+            .filter(|(_, _, verdict)| *verdict == Verdict::Invalid)
+            .map(|(idx, number, _)| (idx, number))
+            .collect();
+
+        assert_eq!(invalid, vec![(14, 127)]);
+    }
+
+    #[test]
+    fn test_duplicates_and_overflow() {
+        let mut validator = XmasValidator::new(3);
+        for number in [5, 5, u64::MAX].iter() {
+            validator.push(*number);
+        }
+
+        assert_eq!(validator.check(10), Verdict::Invalid);
+        assert_eq!(validator.check(u64::MAX), Verdict::Invalid);
+
+        validator.push(1);
+        assert_eq!(validator.check(6), Verdict::Valid);
+        validator.push(2);
+        assert_eq!(validator.check(6), Verdict::Invalid);
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        let numbers = vec![1, 2, 3, 100, 103, 5];
+
+        let strict: Vec<(usize, u64)> = invalid_entries(numbers.clone(), 3, Mode::Strict).map(|entry| (entry.index, entry.number)).collect();
+        assert_eq!(strict, vec![(3, 100), (5, 5)]);
+
+        let healing: Vec<InvalidEntry> = invalid_entries(numbers, 3, Mode::SelfHealing).collect();
+        assert_eq!(healing, vec![
+            InvalidEntry { index: 3, number: 100, window: vec![1, 2, 3] },
+            InvalidEntry { index: 4, number: 103, window: vec![1, 2, 3] },
+        ]);
+        assert_eq!(healing[1].to_string(), "#5: 103 is not the sum of two of [1, 2, 3]");
+    }
+}
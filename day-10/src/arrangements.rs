@@ -0,0 +1,75 @@
+use num_bigint::BigUint;
+
+use crate::adapters::sorted_ratings;
+
+pub fn count_arrangements(ratings: &[usize], max_step: usize) -> BigUint {
+    let chain = sorted_ratings(ratings);
+    let highest = chain[chain.len() - 1];
+
+    let mut ways = vec![BigUint::default(); chain.len()];
+    ways[0] = BigUint::from(1u8);
+
+    // `window` holds the sum of ways[low..high], the adapters 1..=max_step jolts below chain[idx]
+    let (mut low, mut high, mut window) = (0, 0, BigUint::default());
+    for idx in 1..chain.len() {
+        while high < idx && chain[high] < chain[idx] {
+            window += &ways[high];
+            high += 1;
+        }
+        while low < high && chain[idx] - chain[low] > max_step {
+            window -= &ways[low];
+            low += 1;
+        }
+        ways[idx] = window.clone();
+    }
+
+    chain.iter().zip(ways.iter()).filter(|(rating, _)| **rating == highest).map(|(_, ways)| ways).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{count_combinations, possible_steps_count};
+
+    fn ratings_from_differences(differences: &[usize]) -> Vec<usize> {
+        differences.iter().scan(0, |rating, difference| {
+            *rating += difference;
+            Some(*rating)
+        }).collect()
+    }
+
+    #[test]
+    fn test_samples() {
+        assert_eq!(count_arrangements(&[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4], 3), BigUint::from(8u8));
+        assert_eq!(count_arrangements(&[], 3), BigUint::from(1u8));
+        assert_eq!(count_arrangements(&[4], 3), BigUint::from(0u8));
+        assert_eq!(count_arrangements(&[4], 4), BigUint::from(1u8));
+    }
+
+    #[test]
+    fn test_matches_possible_steps_count() {
+        for differences in [[1].as_ref(), &[1, 1], &[1, 1, 1], &[1, 1, 1, 1], &[1, 2, 1], &[2, 1, 1]].iter() {
+            let expected = possible_steps_count(differences);
+            assert_eq!(count_arrangements(&ratings_from_differences(differences), 3), BigUint::from(expected), "{:?}", differences);
+        }
+
+        for length in 1..=10 {
+            for mask in 0..1u32 << length {
+                let differences: Vec<usize> = (0..length).map(|bit| if mask & 1 << bit == 0 { 1 } else { 3 }).collect();
+                let ratings = ratings_from_differences(&differences);
+                assert_eq!(count_arrangements(&ratings, 3), BigUint::from(count_combinations(ratings.clone())), "{:?}", differences);
+            }
+        }
+    }
+
+    #[test]
+    fn test_general_steps() {
+        assert_eq!(count_arrangements(&[1, 2, 3, 4], 1), BigUint::from(1u8));
+        assert_eq!(count_arrangements(&[1, 2, 3, 4], 2), BigUint::from(5u8));
+        assert_eq!(count_arrangements(&[2, 2, 4], 2), BigUint::from(2u8));
+        assert_eq!(count_arrangements(&[5, 10, 15], 5), BigUint::from(1u8));
+
+        let ratings: Vec<usize> = (1..=200).collect();
+        assert_eq!(count_arrangements(&ratings, 200), BigUint::from(2u8).pow(199));
+    }
+}
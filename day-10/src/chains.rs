@@ -0,0 +1,185 @@
+use num_bigint::{BigUint, RandBigInt};
+use rand::Rng;
+
+use crate::adapters::sorted_ratings;
+
+pub struct Arrangements {
+    chain: Vec<usize>,
+    max_step: usize,
+    to_end: Vec<BigUint>,
+}
+
+pub struct Chains<'a> {
+    arrangements: &'a Arrangements,
+    stack: Vec<(usize, usize)>,
+}
+
+impl Arrangements {
+    pub fn new(ratings: &[usize], max_step: usize) -> Self {
+        let chain = sorted_ratings(ratings);
+        let mut arrangements = Self { to_end: vec![BigUint::default(); chain.len()], chain, max_step };
+
+        for idx in (0..arrangements.chain.len()).rev() {
+            arrangements.to_end[idx] = match arrangements.is_last(idx) {
+                true => BigUint::from(1u8),
+                false => arrangements.successors(idx).map(|next| &arrangements.to_end[next]).sum(),
+            };
+        }
+
+        arrangements
+    }
+
+    fn is_last(&self, idx: usize) -> bool {
+        self.chain[idx] == self.chain[self.chain.len() - 1]
+    }
+
+    fn successors(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let rating = self.chain[idx];
+        (idx + 1..self.chain.len())
+            .skip_while(move |next| self.chain[*next] == rating)
+            .take_while(move |next| self.chain[*next] - rating <= self.max_step)
+            .filter(move |next| self.to_end[*next] > BigUint::default())
+    }
+
+    fn ratings(&self, path: &[usize]) -> Vec<usize> {
+        path.iter().map(|idx| self.chain[*idx]).collect()
+    }
+
+    pub fn count(&self) -> &BigUint {
+        &self.to_end[0]
+    }
+
+    pub fn iter(&self) -> Chains<'_> {
+        let stack = if self.to_end[0] > BigUint::default() { vec![(0, 0)] } else { Vec::new() };
+        Chains { arrangements: self, stack }
+    }
+
+    pub fn nth_chain(&self, rank: &BigUint) -> Option<Vec<usize>> {
+        if rank >= self.count() {
+            return None;
+        }
+
+        let mut rank = rank.clone();
+        let mut path = Vec::new();
+        let mut idx = 0;
+        while !self.is_last(idx) {
+            for next in self.successors(idx) {
+                if rank < self.to_end[next] {
+                    idx = next;
+                    break;
+                }
+                rank -= &self.to_end[next];
+            }
+            path.push(idx);
+        }
+
+        Some(self.ratings(&path))
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<usize>> {
+        if self.count() == &BigUint::default() {
+            return None;
+        }
+        self.nth_chain(&rng.gen_biguint_below(self.count()))
+    }
+
+    pub fn shortest(&self) -> Option<Vec<usize>> {
+        if self.count() == &BigUint::default() {
+            return None;
+        }
+
+        let mut distance = vec![usize::MAX; self.chain.len()];
+        for idx in (0..self.chain.len()).rev() {
+            distance[idx] = match self.is_last(idx) {
+                true => 0,
+                false => self.successors(idx).map(|next| distance[next].saturating_add(1)).min().unwrap_or(usize::MAX),
+            };
+        }
+
+        let mut path = Vec::new();
+        let mut idx = 0;
+        while !self.is_last(idx) {
+            idx = self.successors(idx).find(|next| distance[*next] + 1 == distance[idx])?;
+            path.push(idx);
+        }
+
+        Some(self.ratings(&path))
+    }
+}
+
+impl Iterator for Chains<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((idx, cursor)) = self.stack.last_mut() {
+            if self.arrangements.is_last(*idx) {
+                let path: Vec<usize> = self.stack.iter().skip(1).map(|(idx, _)| *idx).collect();
+                self.stack.pop();
+                return Some(self.arrangements.ratings(&path));
+            }
+
+            match self.arrangements.successors(*idx).nth(*cursor) {
+                Some(next) => {
+                    *cursor += 1;
+                    self.stack.push((next, 0));
+                },
+                None => { self.stack.pop(); },
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrangements::count_arrangements;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+
+    const SAMPLE: [usize; 11] = [16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+
+    #[test]
+    fn test_iter() {
+        let arrangements = Arrangements::new(&SAMPLE, 3);
+        let chains: Vec<Vec<usize>> = arrangements.iter().collect();
+
+        assert_eq!(chains.len(), 8);
+        assert_eq!(chains[0], vec![1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19]);
+        assert_eq!(chains[7], vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        assert!(chains.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for (rank, chain) in chains.iter().enumerate() {
+            assert_eq!(arrangements.nth_chain(&BigUint::from(rank)).as_ref(), Some(chain));
+        }
+        assert_eq!(arrangements.nth_chain(&BigUint::from(8u8)), None);
+
+        let ratings = [28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3];
+        assert_eq!(BigUint::from(Arrangements::new(&ratings, 3).iter().count()), count_arrangements(&ratings, 3));
+    }
+
+    #[test]
+    fn test_sample() {
+        let arrangements = Arrangements::new(&SAMPLE, 3);
+        let mut rng = StdRng::seed_from_u64(10);
+
+        let mut seen: HashMap<Vec<usize>, usize> = HashMap::new();
+        for _ in 0..800 {
+            *seen.entry(arrangements.sample(&mut rng).unwrap()).or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), 8);
+        assert!(seen.values().all(|count| (50..150).contains(count)));
+
+        assert_eq!(Arrangements::new(&[4], 3).sample(&mut rng), None);
+    }
+
+    #[test]
+    fn test_shortest() {
+        assert_eq!(Arrangements::new(&SAMPLE, 3).shortest(), Some(vec![1, 4, 7, 10, 12, 15, 16, 19]));
+        assert_eq!(Arrangements::new(&[1, 2, 3, 4, 5, 6], 3).shortest(), Some(vec![3, 6]));
+        assert_eq!(Arrangements::new(&[], 3).shortest(), Some(vec![]));
+        assert_eq!(Arrangements::new(&[4], 3).shortest(), None);
+        assert_eq!(Arrangements::new(&[4], 3).iter().next(), None);
+    }
+}